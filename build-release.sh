@@ -47,7 +47,7 @@ cd silk-spool
 
 # Clean previous builds
 print_status "Cleaning previous builds..."
-rm -rf src-tauri/target/release/bundle/
+rm -rf target/release/bundle/
 rm -rf dist/
 
 # Build the application
@@ -61,8 +61,8 @@ if command -v codesign &> /dev/null; then
     # Check if we have a development certificate
     CERT_ID=$(security find-identity -v -p codesigning | grep "Apple Development" | head -1 | awk '{print $2}')
     if [ ! -z "$CERT_ID" ]; then
-        if [ -d "src-tauri/target/release/bundle/macos/Silk Spool.app" ]; then
-            codesign --force --sign "$CERT_ID" "src-tauri/target/release/bundle/macos/Silk Spool.app"
+        if [ -d "target/release/bundle/macos/Silk Spool.app" ]; then
+            codesign --force --sign "$CERT_ID" "target/release/bundle/macos/Silk Spool.app"
             if [ $? -eq 0 ]; then
                 print_success "App bundle signed successfully"
             else
@@ -88,8 +88,8 @@ fi
 print_status "Copying release files..."
 
 # macOS DMG
-if [ -f "src-tauri/target/release/bundle/dmg/Silk Spool_0.3.0_aarch64.dmg" ]; then
-    cp "src-tauri/target/release/bundle/dmg/Silk Spool_0.3.0_aarch64.dmg" "../$RELEASE_DIR/SilkSpool-0.3.0-macOS-ARM64.dmg"
+if [ -f "target/release/bundle/dmg/Silk Spool_0.3.0_aarch64.dmg" ]; then
+    cp "target/release/bundle/dmg/Silk Spool_0.3.0_aarch64.dmg" "../$RELEASE_DIR/SilkSpool-0.3.0-macOS-ARM64.dmg"
     
     # Note: DMG signing is not needed since we sign the app bundle before creating the DMG
     print_status "DMG created with signed app bundle"
@@ -98,14 +98,14 @@ if [ -f "src-tauri/target/release/bundle/dmg/Silk Spool_0.3.0_aarch64.dmg" ]; th
 fi
 
 # macOS App Bundle
-if [ -d "src-tauri/target/release/bundle/macos/Silk Spool.app" ]; then
-    cp -r "src-tauri/target/release/bundle/macos/Silk Spool.app" "../$RELEASE_DIR/"
+if [ -d "target/release/bundle/macos/Silk Spool.app" ]; then
+    cp -r "target/release/bundle/macos/Silk Spool.app" "../$RELEASE_DIR/"
     print_success "macOS App Bundle copied"
 fi
 
 # Windows MSI (if available)
-if [ -f "src-tauri/target/release/bundle/msi/Silk Spool_0.3.0_x64_en-US.msi" ]; then
-    cp "src-tauri/target/release/bundle/msi/Silk Spool_0.3.0_x64_en-US.msi" "../$RELEASE_DIR/SilkSpool-0.3.0-Windows-x64.msi"
+if [ -f "target/release/bundle/msi/Silk Spool_0.3.0_x64_en-US.msi" ]; then
+    cp "target/release/bundle/msi/Silk Spool_0.3.0_x64_en-US.msi" "../$RELEASE_DIR/SilkSpool-0.3.0-Windows-x64.msi"
     print_success "Windows MSI copied"
 fi
 
@@ -0,0 +1,652 @@
+use std::path::{Path, PathBuf};
+
+use clap::{Parser, Subcommand};
+use serde::Serialize;
+
+use silk_spool_core::{
+  catalog::{load_catalog, CatalogMod},
+  config::{add_repo, load_config, RepoEntry},
+  detect::{get_game_status, validate_game_path, GameStatus},
+  doctor::{export_diagnostics, run_doctor, CheckStatus, DoctorReport},
+  error::{Result, SilkSpoolError},
+  installed_mods::get_installed_mods_for_game,
+  installer::uninstall_tracked_mod,
+  logging::{get_log_dir, init_logging, job_span, recent_log_lines},
+  profiles::{switch_profile, ProfileSwitchResult},
+  repository::{get_repository_cache_dir, RepositoryManager},
+  resolver::{describe_incompatibilities, execute_install_plan, InstallPlanResult, Resolver},
+  types::InstallResult,
+  updates::{check_updates, update_all, ModUpdate, UpdateAllResult, UpdateStatus},
+};
+use tracing::Instrument;
+
+/// Manage Hollow Knight: Silksong mods from the command line
+#[derive(Debug, Parser)]
+#[command(name = "silk-spool-cli", version)]
+struct Cli {
+  /// Print results as JSON
+  #[arg(long, global = true)]
+  json: bool,
+
+  /// Game folder to use instead of the configured or detected one
+  #[arg(long, global = true)]
+  game_path: Option<PathBuf>,
+
+  #[command(subcommand)]
+  command: Command,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+  /// Find the game and check BepInEx
+  Detect,
+  /// Manage mod repositories
+  #[command(subcommand)]
+  Repo(RepoCommand),
+  /// Search the cached repositories by ID, title, description or author
+  Search { query: String },
+  /// Install a mod together with its dependencies
+  Install {
+    mod_id: String,
+    /// Install even if the repositories declare it incompatible with an installed mod
+    #[arg(long)]
+    ignore_incompatibilities: bool,
+  },
+  /// Uninstall a mod
+  Uninstall {
+    mod_id: String,
+    /// Uninstall even if other installed mods depend on it
+    #[arg(long)]
+    force: bool,
+  },
+  /// Upgrade every outdated mod that is not pinned
+  Update {
+    /// Only list the available updates
+    #[arg(long)]
+    check: bool,
+  },
+  /// Manage mod profiles
+  #[command(subcommand)]
+  Profile(ProfileCommand),
+  /// Check the installation for common problems
+  Doctor {
+    /// Also save a zip with the report, configuration and logs for a bug report
+    #[arg(long)]
+    export: Option<PathBuf>,
+  },
+  /// Print the most recent lines of the log file
+  Logs {
+    #[arg(long, default_value_t = 50)]
+    lines: usize,
+  },
+}
+
+#[derive(Debug, Subcommand)]
+enum RepoCommand {
+  /// Add a repository with the lowest priority
+  Add {
+    url: String,
+    #[arg(long)]
+    name: Option<String>,
+  },
+  /// Fetch every enabled repository again
+  Refresh,
+  /// List the configured repositories
+  List,
+}
+
+#[derive(Debug, Subcommand)]
+enum ProfileCommand {
+  /// Switch the installed mods to match a profile
+  Switch { name: String },
+}
+
+/// A configured repository and what is cached of it
+#[derive(Debug, Serialize)]
+struct RepoStatus {
+  #[serde(flatten)]
+  entry: RepoEntry,
+  repo_id: Option<String>,
+  mod_count: Option<usize>,
+  last_updated: Option<String>,
+}
+
+/// The outcome of refreshing one repository
+#[derive(Debug, Serialize)]
+struct RepoRefresh {
+  url: String,
+  repo_id: Option<String>,
+  mod_count: usize,
+  error: Option<SilkSpoolError>,
+}
+
+#[derive(Debug, Serialize)]
+struct CliError {
+  error: SilkSpoolError,
+}
+
+fn main() {
+  std::process::exit(run())
+}
+
+/// Run the command line interface and return the process exit code
+fn run() -> i32 {
+  let cli = Cli::parse();
+  let json = cli.json;
+
+  // Output goes to stdout, so log only to the file; a missing log file is not worth failing over
+  let _log_guard = get_log_dir()
+    .and_then(|log_dir| init_logging(&log_dir, false))
+    .ok();
+
+  let runtime = match tokio::runtime::Runtime::new() {
+    Ok(runtime) => runtime,
+    Err(e) => {
+      eprintln!("Failed to start async runtime: {}", e);
+      return 1;
+    }
+  };
+
+  match runtime.block_on(execute(cli)) {
+    Ok(true) => 0,
+    Ok(false) => 1,
+    Err(error) => {
+      if json {
+        print_json(&CliError { error });
+      } else {
+        eprintln!("Error: {}", error);
+      }
+      1
+    }
+  }
+}
+
+/// Run a subcommand, returning whether it fully succeeded
+async fn execute(cli: Cli) -> Result<bool> {
+  let repo_manager =
+    RepositoryManager::new(get_repository_cache_dir().to_string_lossy().to_string());
+  let json = cli.json;
+
+  match cli.command {
+    Command::Detect => {
+      let status = game_status(cli.game_path.as_deref())?;
+      output(json, &status, print_game_status);
+      Ok(status.found)
+    }
+    Command::Repo(RepoCommand::Add { url, name }) => {
+      add_repo(RepoEntry {
+        name,
+        ..RepoEntry::new(url.clone())
+      })?;
+      let message = format!("Added repository {}", url);
+      output(json, &message, |m| println!("{}", m));
+      Ok(true)
+    }
+    Command::Repo(RepoCommand::Refresh) => {
+      let results = refresh_repositories(&repo_manager).await?;
+      output(json, results.as_slice(), print_refresh);
+      Ok(results.iter().all(|r| r.error.is_none()))
+    }
+    Command::Repo(RepoCommand::List) => {
+      let repos = list_repositories(&repo_manager).await?;
+      output(json, repos.as_slice(), print_repositories);
+      Ok(true)
+    }
+    Command::Search { query } => {
+      let catalog = load_catalog(&repo_manager).await?;
+      let matches: Vec<&CatalogMod> = catalog
+        .mods
+        .iter()
+        .filter(|m| mod_matches(m, &query))
+        .collect();
+      output(json, matches.as_slice(), print_mods);
+      Ok(true)
+    }
+    Command::Install {
+      mod_id,
+      ignore_incompatibilities,
+    } => {
+      let game_path = resolve_game_path(cli.game_path)?;
+      let catalog = load_catalog(&repo_manager).await?;
+      let repositories = catalog.as_repositories();
+      let installed = get_installed_mods_for_game(&game_path)?;
+      let plan = Resolver::new(&repositories, &installed).plan_install(&mod_id)?;
+
+      if !plan.incompatibilities.is_empty() && !ignore_incompatibilities {
+        return Err(describe_incompatibilities(&plan.incompatibilities));
+      }
+
+      let result = execute_install_plan(&plan, &game_path, &catalog)
+        .instrument(job_span("install"))
+        .await;
+      output(json, &result, print_install);
+      Ok(result.failed.is_empty())
+    }
+    Command::Uninstall { mod_id, force } => {
+      let game_path = resolve_game_path(cli.game_path)?;
+      if !force {
+        let repositories = load_catalog(&repo_manager).await?.as_repositories();
+        let installed = get_installed_mods_for_game(&game_path)?;
+        let dependents = Resolver::new(&repositories, &installed).dependents_of(&mod_id);
+
+        if !dependents.is_empty() {
+          return Err(SilkSpoolError::Dependency {
+            message: format!(
+              "{} is required by: {} (use --force to remove it anyway)",
+              mod_id,
+              dependents.join(", ")
+            ),
+            mod_id: Some(mod_id),
+          });
+        }
+      }
+
+      let result = job_span("uninstall").in_scope(|| uninstall_tracked_mod(&mod_id, &game_path))?;
+      output(json, &result, print_install_result);
+      Ok(result.success)
+    }
+    Command::Update { check } => {
+      let catalog = load_catalog(&repo_manager).await?;
+      let game_path = resolve_game_path(cli.game_path)?;
+
+      if check {
+        let updates = check_updates(
+          &catalog.as_repositories(),
+          &get_installed_mods_for_game(&game_path)?,
+        );
+        output(json, updates.as_slice(), print_updates);
+        return Ok(true);
+      }
+
+      let result = update_all(&catalog, &game_path)
+        .instrument(job_span("update_all"))
+        .await?;
+      output(json, &result, print_update_all);
+      Ok(result.success)
+    }
+    Command::Profile(ProfileCommand::Switch { name }) => {
+      let game_path = resolve_game_path(cli.game_path)?;
+      let catalog = load_catalog(&repo_manager).await?;
+      let result = switch_profile(&name, &game_path, &catalog)
+        .instrument(job_span("switch_profile"))
+        .await?;
+      output(json, &result, print_profile_switch);
+      Ok(result.failed.is_empty())
+    }
+    Command::Doctor { export } => {
+      let report = run_doctor(cli.game_path.as_deref(), &repo_manager).await;
+      if let Some(path) = &export {
+        export_diagnostics(&report, path)?;
+        if !json {
+          println!("Saved diagnostics to {}\n", path.display());
+        }
+      }
+      output(json, &report, print_doctor);
+      Ok(report.healthy())
+    }
+    Command::Logs { lines } => {
+      let lines = recent_log_lines(&get_log_dir()?, lines)?;
+      output(json, lines.as_slice(), |lines| {
+        lines.iter().for_each(|line| println!("{}", line))
+      });
+      Ok(true)
+    }
+  }
+}
+
+fn game_status(game_path: Option<&Path>) -> Result<GameStatus> {
+  match game_path {
+    Some(path) => validate_game_path(path),
+    None => get_game_status(),
+  }
+}
+
+/// Use the given game path, then the configured one, then the detected one
+fn resolve_game_path(game_path: Option<PathBuf>) -> Result<PathBuf> {
+  if let Some(path) = game_path {
+    return Ok(path);
+  }
+  if let Some(path) = load_config()?.game_path {
+    return Ok(path);
+  }
+
+  get_game_status()?
+    .path
+    .ok_or_else(|| SilkSpoolError::GameNotFound {
+      reason: "pass --game-path to choose the game folder".to_string(),
+    })
+}
+
+async fn refresh_repositories(repo_manager: &RepositoryManager) -> Result<Vec<RepoRefresh>> {
+  let config = load_config()?;
+  let mut results = Vec::new();
+
+  for repo in config.enabled_repos() {
+    let result = match repo_manager.fetch_repository(&repo.url).await {
+      Ok(repository) => RepoRefresh {
+        url: repo.url.clone(),
+        repo_id: Some(repository.repo_id),
+        mod_count: repository.mods.len(),
+        error: None,
+      },
+      Err(error) => RepoRefresh {
+        url: repo.url.clone(),
+        repo_id: None,
+        mod_count: 0,
+        error: Some(error),
+      },
+    };
+    results.push(result);
+  }
+
+  Ok(results)
+}
+
+async fn list_repositories(repo_manager: &RepositoryManager) -> Result<Vec<RepoStatus>> {
+  let mut config = load_config()?;
+  config.repos.sort_by_key(|repo| repo.priority);
+  let cached = repo_manager.get_cached_repositories().await?;
+
+  Ok(
+    config
+      .repos
+      .into_iter()
+      .map(|entry| {
+        let info = cached.iter().find(|info| info.url == entry.url);
+        RepoStatus {
+          repo_id: info.map(|info| info.id.clone()),
+          mod_count: info.map(|info| info.mod_count),
+          last_updated: info.and_then(|info| info.last_updated.clone()),
+          entry,
+        }
+      })
+      .collect(),
+  )
+}
+
+fn mod_matches(catalog_mod: &CatalogMod, query: &str) -> bool {
+  let query = query.to_lowercase();
+  let definition = &catalog_mod.definition;
+
+  [&definition.id, &definition.title, &definition.description]
+    .into_iter()
+    .chain(&definition.authors)
+    .any(|field| field.to_lowercase().contains(&query))
+}
+
+/// Print a result as JSON, or as text using `text`
+fn output<T: Serialize + ?Sized>(json: bool, value: &T, text: impl FnOnce(&T)) {
+  if json {
+    print_json(value);
+  } else {
+    text(value);
+  }
+}
+
+fn print_json<T: Serialize + ?Sized>(value: &T) {
+  match serde_json::to_string_pretty(value) {
+    Ok(text) => println!("{}", text),
+    Err(e) => eprintln!("Failed to serialize output: {}", e),
+  }
+}
+
+fn print_game_status(status: &GameStatus) {
+  match &status.path {
+    Some(path) if status.found => println!("Game: {}", path.display()),
+    _ => println!("Game: not found"),
+  }
+  println!("BepInEx: {}", status.bepinex.message);
+}
+
+fn print_refresh(results: &[RepoRefresh]) {
+  for result in results {
+    match &result.error {
+      None => println!("ok      {} ({} mods)", result.url, result.mod_count),
+      Some(error) => println!("failed  {}: {}", result.url, error),
+    }
+  }
+}
+
+fn print_repositories(repos: &[RepoStatus]) {
+  for repo in repos {
+    let state = if repo.entry.enabled {
+      "enabled"
+    } else {
+      "disabled"
+    };
+    let mods = repo
+      .mod_count
+      .map(|count| format!("{} mods", count))
+      .unwrap_or_else(|| "not fetched".to_string());
+    println!(
+      "{:>3}  {:<8}  {}  ({})",
+      repo.entry.priority, state, repo.entry.url, mods
+    );
+  }
+}
+
+fn print_mods(mods: &[&CatalogMod]) {
+  if mods.is_empty() {
+    println!("No mods found");
+  }
+  for catalog_mod in mods {
+    let definition = &catalog_mod.definition;
+    println!(
+      "{}  {}  {}  [{}]",
+      definition.id, definition.version, definition.title, catalog_mod.repo_id
+    );
+  }
+}
+
+fn print_install(result: &InstallPlanResult) {
+  for mod_id in &result.installed {
+    println!("Installed {}", mod_id);
+  }
+  for failure in &result.failed {
+    println!("Failed {}: {}", failure.mod_id, failure.error);
+  }
+  for mod_id in &result.skipped {
+    println!("Skipped {}", mod_id);
+  }
+}
+
+fn print_install_result(result: &InstallResult) {
+  println!("{}", result.message);
+}
+
+fn print_updates(updates: &[ModUpdate]) {
+  if updates.is_empty() {
+    println!("All mods are up to date");
+  }
+  for update in updates {
+    let pinned = if update.pinned { " (pinned)" } else { "" };
+    println!(
+      "{}  {} -> {}{}",
+      update.mod_id, update.installed_version, update.available_version, pinned
+    );
+  }
+}
+
+fn print_update_all(result: &UpdateAllResult) {
+  if result.results.is_empty() {
+    println!("All mods are up to date");
+  }
+  for entry in &result.results {
+    let status = match entry.status {
+      UpdateStatus::Updated => "updated",
+      UpdateStatus::Installed => "installed",
+      UpdateStatus::Pinned => "pinned",
+      UpdateStatus::Failed => "failed",
+      UpdateStatus::Cancelled => "cancelled",
+    };
+    let from = entry.from_version.as_deref().unwrap_or("-");
+    println!(
+      "{:<9}  {}  {} -> {}",
+      status, entry.mod_id, from, entry.to_version
+    );
+    if let Some(error) = &entry.error {
+      println!("           {}", error);
+    }
+  }
+  for error in &result.rollback_errors {
+    println!("Rollback problem: {}", error);
+  }
+}
+
+fn print_profile_switch(result: &ProfileSwitchResult) {
+  if result.failed.is_empty() {
+    println!("Switched to profile {}", result.profile);
+  } else {
+    println!("Partly switched to profile {}", result.profile);
+  }
+  let groups = [
+    ("Installed", &result.installed),
+    ("Removed", &result.removed),
+    ("Enabled", &result.enabled),
+    ("Disabled", &result.disabled),
+  ];
+  for (label, mods) in groups {
+    if !mods.is_empty() {
+      println!("{}: {}", label, mods.join(", "));
+    }
+  }
+  for failure in &result.failed {
+    println!("Failed {}: {}", failure.mod_id, failure.error);
+  }
+}
+
+fn print_doctor(report: &DoctorReport) {
+  for check in &report.checks {
+    let status = match check.status {
+      CheckStatus::Ok => "ok",
+      CheckStatus::Warning => "warning",
+      CheckStatus::Error => "error",
+    };
+    println!("{:<7}  {:<14}  {}", status, check.name, check.message);
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn parse(args: &[&str]) -> Cli {
+    Cli::try_parse_from(std::iter::once("silk-spool-cli").chain(args.iter().copied())).unwrap()
+  }
+
+  #[test]
+  fn test_parse_global_flags() {
+    let cli = parse(&["detect", "--json", "--game-path", "/games/Silksong"]);
+    assert!(cli.json);
+    assert_eq!(cli.game_path, Some(PathBuf::from("/games/Silksong")));
+    assert!(matches!(cli.command, Command::Detect));
+  }
+
+  #[test]
+  fn test_parse_repo_commands() {
+    match parse(&[
+      "repo",
+      "add",
+      "https://example.com/mods.json",
+      "--name",
+      "Main",
+    ])
+    .command
+    {
+      Command::Repo(RepoCommand::Add { url, name }) => {
+        assert_eq!(url, "https://example.com/mods.json");
+        assert_eq!(name.as_deref(), Some("Main"));
+      }
+      other => panic!("unexpected command {:?}", other),
+    }
+    assert!(matches!(
+      parse(&["repo", "refresh"]).command,
+      Command::Repo(RepoCommand::Refresh)
+    ));
+    assert!(matches!(
+      parse(&["repo", "list"]).command,
+      Command::Repo(RepoCommand::List)
+    ));
+  }
+
+  #[test]
+  fn test_parse_mod_commands() {
+    assert!(matches!(
+      parse(&["search", "map"]).command,
+      Command::Search { query } if query == "map"
+    ));
+    assert!(matches!(
+      parse(&["install", "a", "--ignore-incompatibilities"]).command,
+      Command::Install { mod_id, ignore_incompatibilities: true } if mod_id == "a"
+    ));
+    assert!(matches!(
+      parse(&["install", "a"]).command,
+      Command::Install {
+        ignore_incompatibilities: false,
+        ..
+      }
+    ));
+    assert!(matches!(
+      parse(&["uninstall", "a", "--force"]).command,
+      Command::Uninstall { mod_id, force: true } if mod_id == "a"
+    ));
+    assert!(matches!(
+      parse(&["update", "--check"]).command,
+      Command::Update { check: true }
+    ));
+    assert!(matches!(
+      parse(&["update"]).command,
+      Command::Update { check: false }
+    ));
+  }
+
+  #[test]
+  fn test_parse_other_commands() {
+    assert!(matches!(
+      parse(&["profile", "switch", "speedrun"]).command,
+      Command::Profile(ProfileCommand::Switch { name }) if name == "speedrun"
+    ));
+    assert!(matches!(
+      parse(&["doctor"]).command,
+      Command::Doctor { export: None }
+    ));
+    assert!(matches!(
+      parse(&["doctor", "--export", "diagnostics.zip"]).command,
+      Command::Doctor { export: Some(path) } if path == Path::new("diagnostics.zip")
+    ));
+    assert!(matches!(
+      parse(&["logs"]).command,
+      Command::Logs { lines: 50 }
+    ));
+    assert!(matches!(
+      parse(&["logs", "--lines", "10"]).command,
+      Command::Logs { lines: 10 }
+    ));
+  }
+
+  #[test]
+  fn test_parse_rejects_bad_arguments() {
+    for args in [
+      &[][..],
+      &["install"][..],
+      &["repo"][..],
+      &["logs", "--lines", "many"][..],
+      &["unknown"][..],
+    ] {
+      let args = std::iter::once("silk-spool-cli").chain(args.iter().copied());
+      assert!(Cli::try_parse_from(args).is_err());
+    }
+  }
+
+  #[test]
+  fn test_json_errors_carry_the_error_code() {
+    let error = CliError {
+      error: SilkSpoolError::mod_not_found("missing-mod"),
+    };
+
+    let value = serde_json::to_value(&error).unwrap();
+    assert_eq!(value["error"]["code"], "mod_not_found");
+    assert_eq!(value["error"]["code"], error.error.code());
+    assert!(value["error"]["message"].is_string());
+  }
+}
@@ -0,0 +1,329 @@
+use std::{collections::HashMap, path::PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+  config::load_config,
+  error::Result,
+  installer::DownloadSource,
+  repository::{
+    local_repository_dir, repository_base_url, resolve_repository_url, RepositoryManager,
+  },
+  schema::{Mod, ModRepository, SCHEMA_VERSION},
+};
+
+/// A repository taking part in the catalog
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CatalogRepository {
+  pub repo_id: String,
+  pub name: String,
+  pub url: String,
+  pub version: u32,
+}
+
+/// A mod in the catalog together with where it comes from
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CatalogMod {
+  #[serde(flatten)]
+  pub definition: Mod,
+  pub repo_id: String,
+  pub repo_url: String,
+  /// Lower-priority repositories that list the same mod ID
+  pub shadowed: Vec<String>,
+}
+
+/// Every cached repository merged into one list of mods
+///
+/// When several repositories list the same mod ID, the one with the highest priority wins.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Catalog {
+  /// Repositories in priority order, highest first
+  pub repositories: Vec<CatalogRepository>,
+  pub mods: Vec<CatalogMod>,
+}
+
+impl Catalog {
+  /// Merge repositories, given with the URL they were fetched from
+  ///
+  /// `priority` lists repository URLs from highest to lowest priority; repositories that are not
+  /// in it rank last.
+  pub fn build(sources: Vec<(ModRepository, String)>, priority: &[String]) -> Self {
+    let mut sources = sources;
+    sources.sort_by_key(|(repo, url)| {
+      let rank = priority.iter().position(|p| p == url).unwrap_or(usize::MAX);
+      (rank, repo.repo_id.clone())
+    });
+
+    let mut catalog = Catalog::default();
+    let mut positions: HashMap<String, usize> = HashMap::new();
+
+    for (repo, url) in sources {
+      catalog.repositories.push(CatalogRepository {
+        repo_id: repo.repo_id.clone(),
+        name: repo.name,
+        url: url.clone(),
+        version: repo.version,
+      });
+
+      for definition in repo.mods {
+        match positions.get(&definition.id) {
+          Some(&position) => catalog.mods[position].shadowed.push(repo.repo_id.clone()),
+          None => {
+            positions.insert(definition.id.clone(), catalog.mods.len());
+            catalog.mods.push(CatalogMod {
+              definition,
+              repo_id: repo.repo_id.clone(),
+              repo_url: url.clone(),
+              shadowed: Vec::new(),
+            });
+          }
+        }
+      }
+    }
+
+    catalog
+  }
+
+  /// Find the winning definition of a mod
+  pub fn find(&self, mod_id: &str) -> Option<&CatalogMod> {
+    self.mods.iter().find(|m| m.definition.id == mod_id)
+  }
+
+  /// Resolve a URL given relative to the repository that lists a mod
+  ///
+  /// Absolute URLs, and URLs of mods that are not in the catalog, are returned unchanged.
+  pub fn resolve_url(&self, mod_id: &str, url: &str) -> String {
+    self
+      .find(mod_id)
+      .and_then(|m| repository_base_url(&m.repo_url))
+      .map(|base| resolve_repository_url(&base, url))
+      .unwrap_or_else(|| url.to_string())
+  }
+
+  /// Get where to download a mod from, allowing `file://` URLs if a local repository lists it
+  pub fn download_source(&self, mod_id: &str, url: &str) -> DownloadSource {
+    DownloadSource {
+      url: url.to_string(),
+      local_root: self.local_root(mod_id),
+    }
+  }
+
+  /// Get the directory of the local repository that lists a mod
+  pub fn local_root(&self, mod_id: &str) -> Option<PathBuf> {
+    self
+      .find(mod_id)
+      .and_then(|m| local_repository_dir(&m.repo_url))
+  }
+
+  /// Get the catalog as repositories, each holding only the mods it wins, in priority order
+  pub fn as_repositories(&self) -> Vec<ModRepository> {
+    self
+      .repositories
+      .iter()
+      .map(|repo| ModRepository {
+        schema_version: SCHEMA_VERSION,
+        repo_id: repo.repo_id.clone(),
+        name: repo.name.clone(),
+        version: repo.version,
+        mods: self
+          .mods
+          .iter()
+          .filter(|m| m.repo_url == repo.url)
+          .map(|m| m.definition.clone())
+          .collect(),
+        extra: Default::default(),
+      })
+      .collect()
+  }
+}
+
+/// Build the catalog from the cached repositories, ranked by the configured repository priorities
+pub async fn load_catalog(repo_manager: &RepositoryManager) -> Result<Catalog> {
+  let priority: Vec<String> = load_config()?
+    .enabled_repos()
+    .into_iter()
+    .map(|repo| repo.url.clone())
+    .collect();
+  let mut sources = Vec::new();
+
+  for info in repo_manager.get_cached_repositories().await? {
+    // Removed and disabled repositories can stay cached but are left out of the catalog
+    if !priority.contains(&info.url) {
+      continue;
+    }
+
+    if let Ok(repo) = repo_manager.load_cached_repository(&info.url).await {
+      sources.push((repo, info.url));
+    }
+  }
+
+  Ok(Catalog::build(sources, &priority))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::config::{add_repo, test_dirs, RepoEntry};
+
+  fn repo_mod(id: &str, version: &str) -> Mod {
+    Mod {
+      id: id.to_string(),
+      title: id.to_string(),
+      version: version.to_string(),
+      description: String::new(),
+      requirements: vec![],
+      images: vec![],
+      downloads: vec![],
+      homepage: None,
+      authors: vec![],
+      game_version: "1.0".to_string(),
+      updated_at: "2024-01-01".to_string(),
+      dependencies: vec![],
+      conflicts: vec![],
+      provides: vec![],
+      versions: vec![],
+      extra: Default::default(),
+    }
+  }
+
+  fn source(repo_id: &str, mods: Vec<Mod>) -> (ModRepository, String) {
+    (
+      ModRepository {
+        schema_version: SCHEMA_VERSION,
+        repo_id: repo_id.to_string(),
+        name: repo_id.to_string(),
+        version: 1,
+        mods,
+        extra: Default::default(),
+      },
+      format!("https://{}.example.com/mods.json", repo_id),
+    )
+  }
+
+  #[test]
+  fn test_build_prefers_higher_priority_repositories() {
+    let catalog = Catalog::build(
+      vec![
+        source(
+          "community",
+          vec![repo_mod("shared", "2.0.0"), repo_mod("extra", "1.0.0")],
+        ),
+        source("official", vec![repo_mod("shared", "1.0.0")]),
+        source("unlisted", vec![repo_mod("shared", "3.0.0")]),
+      ],
+      &[
+        "https://official.example.com/mods.json".to_string(),
+        "https://community.example.com/mods.json".to_string(),
+      ],
+    );
+
+    let shared = catalog.find("shared").unwrap();
+    assert_eq!(shared.repo_id, "official");
+    assert_eq!(shared.repo_url, "https://official.example.com/mods.json");
+    assert_eq!(shared.definition.version, "1.0.0");
+    assert_eq!(shared.shadowed, vec!["community", "unlisted"]);
+    assert_eq!(catalog.find("extra").unwrap().repo_id, "community");
+    assert_eq!(
+      catalog.resolve_url("extra", "files/extra.zip"),
+      "https://community.example.com/files/extra.zip"
+    );
+    assert_eq!(catalog.resolve_url("missing", "files/a.zip"), "files/a.zip");
+  }
+
+  #[test]
+  fn test_as_repositories_drops_shadowed_mods() {
+    let catalog = Catalog::build(
+      vec![
+        source("b", vec![repo_mod("shared", "2.0.0")]),
+        source("a", vec![repo_mod("shared", "1.0.0")]),
+      ],
+      &["https://a.example.com/mods.json".to_string()],
+    );
+
+    let repositories = catalog.as_repositories();
+    assert_eq!(repositories[0].repo_id, "a");
+    assert_eq!(repositories[0].mods.len(), 1);
+    assert!(repositories[1].mods.is_empty());
+  }
+
+  #[test]
+  fn test_as_repositories_keeps_same_id_repositories_apart() {
+    let (first, _) = source("mirror", vec![repo_mod("a", "1.0.0")]);
+    let (second, _) = source("mirror", vec![repo_mod("b", "1.0.0")]);
+    let catalog = Catalog::build(
+      vec![
+        (first, "https://one.example.com/mods.json".to_string()),
+        (second, "https://two.example.com/mods.json".to_string()),
+      ],
+      &[
+        "https://one.example.com/mods.json".to_string(),
+        "https://two.example.com/mods.json".to_string(),
+      ],
+    );
+
+    let repositories = catalog.as_repositories();
+    assert_eq!(repositories.len(), 2);
+    assert_eq!(repositories[0].mods.len(), 1);
+    assert_eq!(repositories[0].mods[0].id, "a");
+    assert_eq!(repositories[1].mods.len(), 1);
+    assert_eq!(repositories[1].mods[0].id, "b");
+  }
+
+  #[tokio::test]
+  async fn test_load_catalog_skips_repositories_no_longer_configured() {
+    let dirs = test_dirs::isolate();
+    let manager = RepositoryManager::new(dirs.path().to_string_lossy().to_string());
+    for (repo_id, mod_id) in [("kept", "a"), ("removed", "b")] {
+      let (repository, url) = source(repo_id, vec![repo_mod(mod_id, "1.0.0")]);
+      let entry = serde_json::json!({
+        "url": url,
+        "fetched_at": "2024-01-01T00:00:00Z",
+        "repository": repository,
+      });
+      std::fs::write(
+        dirs.path().join(format!("repo_{}.json", repo_id)),
+        entry.to_string(),
+      )
+      .unwrap();
+    }
+    add_repo(RepoEntry::new(
+      "https://kept.example.com/mods.json".to_string(),
+    ))
+    .unwrap();
+
+    let catalog = load_catalog(&manager).await.unwrap();
+    assert_eq!(catalog.repositories.len(), 1);
+    assert!(catalog.find("a").is_some());
+    assert!(catalog.find("b").is_none());
+  }
+
+  #[tokio::test]
+  async fn test_load_catalog_keeps_legacy_bare_caches() {
+    let dirs = test_dirs::isolate();
+    let manager = RepositoryManager::new(dirs.path().to_string_lossy().to_string());
+    let (kept, kept_url) = source("kept", vec![repo_mod("a", "1.0.0")]);
+    let entry = serde_json::json!({
+      "url": kept_url,
+      "fetched_at": "2024-01-01T00:00:00Z",
+      "repository": kept,
+    });
+    std::fs::write(dirs.path().join("repo_kept.json"), entry.to_string()).unwrap();
+    // Older versions cached the bare repository under its ID
+    let (legacy, legacy_url) = source("legacy", vec![repo_mod("b", "1.0.0")]);
+    std::fs::write(
+      dirs.path().join("repo_legacy.json"),
+      serde_json::to_string(&legacy).unwrap(),
+    )
+    .unwrap();
+    add_repo(RepoEntry::new(kept_url)).unwrap();
+    add_repo(RepoEntry::new(legacy_url.clone())).unwrap();
+
+    let catalog = load_catalog(&manager).await.unwrap();
+    assert_eq!(catalog.repositories.len(), 2);
+    assert_eq!(catalog.find("b").unwrap().repo_url, legacy_url);
+    assert!(!dirs.path().join("repo_legacy.json").exists());
+
+    let catalog = load_catalog(&manager).await.unwrap();
+    assert!(catalog.find("b").is_some());
+  }
+}
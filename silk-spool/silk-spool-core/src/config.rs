@@ -0,0 +1,515 @@
+use std::{
+  fs,
+  path::{Path, PathBuf},
+};
+
+use serde::{Deserialize, Deserializer, Serialize};
+
+use crate::error::{Result, SilkSpoolError};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AppConfig {
+  pub game_path: Option<PathBuf>,
+  #[serde(deserialize_with = "deserialize_repos")]
+  pub repos: Vec<RepoEntry>,
+  pub ui: UiConfig,
+  /// Never touch the network; repositories and downloads come from the caches only
+  #[serde(default)]
+  pub offline_mode: bool,
+}
+
+/// A configured mod repository
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RepoEntry {
+  pub url: String,
+  #[serde(default)]
+  pub name: Option<String>,
+  #[serde(default = "default_true")]
+  pub enabled: bool,
+  /// Lower numbers win when several repositories list the same mod
+  #[serde(default)]
+  pub priority: u32,
+  /// Minisign public key the repository must be signed with
+  #[serde(default)]
+  pub pinned_key: Option<String>,
+  #[serde(default)]
+  pub auth: Option<RepoAuth>,
+  /// A cached copy younger than this is used instead of fetching again
+  #[serde(default)]
+  pub refresh_interval_minutes: Option<u64>,
+}
+
+impl RepoEntry {
+  pub fn new(url: String) -> Self {
+    Self {
+      url,
+      name: None,
+      enabled: true,
+      priority: 0,
+      pinned_key: None,
+      auth: None,
+      refresh_interval_minutes: None,
+    }
+  }
+}
+
+/// Credentials sent when fetching a repository
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum RepoAuth {
+  Bearer { token: String },
+  Basic { username: String, password: String },
+}
+
+// Secrets are left out so that configs can be logged
+impl std::fmt::Debug for RepoAuth {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    match self {
+      RepoAuth::Bearer { .. } => f
+        .debug_struct("Bearer")
+        .field("token", &"<redacted>")
+        .finish(),
+      RepoAuth::Basic { username, .. } => f
+        .debug_struct("Basic")
+        .field("username", username)
+        .field("password", &"<redacted>")
+        .finish(),
+    }
+  }
+}
+
+fn default_true() -> bool {
+  true
+}
+
+/// Read the repository list, upgrading the old plain list of URLs
+fn deserialize_repos<'de, D>(deserializer: D) -> Result<Vec<RepoEntry>, D::Error>
+where
+  D: Deserializer<'de>,
+{
+  #[derive(Deserialize)]
+  #[serde(untagged)]
+  enum StoredRepo {
+    Url(String),
+    Entry(RepoEntry),
+  }
+
+  let stored = Vec::<StoredRepo>::deserialize(deserializer)?;
+
+  Ok(
+    stored
+      .into_iter()
+      .enumerate()
+      .map(|(index, repo)| match repo {
+        StoredRepo::Url(url) => RepoEntry {
+          priority: index as u32,
+          ..RepoEntry::new(url)
+        },
+        StoredRepo::Entry(entry) => entry,
+      })
+      .collect(),
+  )
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UiConfig {
+  pub theme: String,
+  pub window_size: WindowSize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WindowSize {
+  pub width: u32,
+  pub height: u32,
+}
+
+impl AppConfig {
+  /// Get the enabled repositories, highest priority first
+  pub fn enabled_repos(&self) -> Vec<&RepoEntry> {
+    let mut repos: Vec<&RepoEntry> = self.repos.iter().filter(|repo| repo.enabled).collect();
+    repos.sort_by_key(|repo| repo.priority);
+    repos
+  }
+
+  /// Order repositories by the given URLs, keeping unlisted ones after them in their old order
+  pub fn reorder_repos(&mut self, urls: &[String]) {
+    self.repos.sort_by_key(|repo| {
+      let position = urls.iter().position(|url| *url == repo.url);
+      (position.unwrap_or(usize::MAX), repo.priority)
+    });
+
+    for (index, repo) in self.repos.iter_mut().enumerate() {
+      repo.priority = index as u32;
+    }
+  }
+}
+
+impl Default for AppConfig {
+  fn default() -> Self {
+    Self {
+      game_path: None,
+      repos: Vec::new(),
+      ui: UiConfig {
+        theme: "dark".to_string(),
+        window_size: WindowSize {
+          width: 1200,
+          height: 800,
+        },
+      },
+      offline_mode: false,
+    }
+  }
+}
+
+/// Get the application data directory
+pub fn get_app_data_dir() -> Result<PathBuf> {
+  #[cfg(test)]
+  if let Some(root) = test_dirs::root() {
+    return Ok(root.join("data"));
+  }
+
+  dirs::data_dir()
+    .ok_or_else(|| SilkSpoolError::other("Could not find data directory"))
+    .map(|dir| dir.join("silk-spool"))
+}
+
+/// Get the application cache directory
+pub fn get_app_cache_dir() -> Result<PathBuf> {
+  #[cfg(test)]
+  if let Some(root) = test_dirs::root() {
+    return Ok(root.join("cache"));
+  }
+
+  dirs::cache_dir()
+    .ok_or_else(|| SilkSpoolError::other("Failed to get cache directory"))
+    .map(|dir| dir.join("silk-spool"))
+}
+
+/// Lets tests run against their own data and cache directories instead of the user's
+#[cfg(test)]
+pub mod test_dirs {
+  use std::{
+    cell::RefCell,
+    path::{Path, PathBuf},
+  };
+
+  use tempfile::TempDir;
+
+  thread_local! {
+    static ROOT: RefCell<Option<PathBuf>> = const { RefCell::new(None) };
+  }
+
+  /// Temporary data and cache directories, in use on this thread until dropped
+  pub struct IsolatedDirs {
+    root: TempDir,
+  }
+
+  impl IsolatedDirs {
+    pub fn path(&self) -> &Path {
+      self.root.path()
+    }
+  }
+
+  impl Drop for IsolatedDirs {
+    fn drop(&mut self) {
+      ROOT.with(|root| root.borrow_mut().take());
+    }
+  }
+
+  /// Point the data and cache directories of the current thread at a new empty folder
+  pub fn isolate() -> IsolatedDirs {
+    let root = TempDir::new().expect("create temporary app directories");
+    ROOT.with(|current| *current.borrow_mut() = Some(root.path().to_path_buf()));
+    IsolatedDirs { root }
+  }
+
+  pub fn root() -> Option<PathBuf> {
+    ROOT.with(|root| root.borrow().clone())
+  }
+}
+
+/// Get the configuration file path
+pub fn get_config_path() -> Result<PathBuf> {
+  let app_dir = get_app_data_dir()?;
+  Ok(app_dir.join("config.json"))
+}
+
+/// Load configuration from file
+pub fn load_config() -> Result<AppConfig> {
+  let config_path = get_config_path()?;
+
+  if !config_path.exists() {
+    // Return default config if file doesn't exist
+    return Ok(AppConfig::default());
+  }
+
+  let content = fs::read_to_string(&config_path)
+    .map_err(|e| SilkSpoolError::io("read config file", &config_path, e))?;
+
+  let config: AppConfig =
+    serde_json::from_str(&content).map_err(|e| SilkSpoolError::parse("config file", e))?;
+
+  Ok(config)
+}
+
+/// Save configuration to file
+pub fn save_config(config: &AppConfig) -> Result<()> {
+  let config_path = get_config_path()?;
+  let app_dir = config_path.parent().unwrap();
+
+  // Create app directory if it doesn't exist
+  fs::create_dir_all(app_dir)
+    .map_err(|e| SilkSpoolError::io("create app directory", app_dir, e))?;
+
+  let content = serde_json::to_string_pretty(config)
+    .map_err(|e| SilkSpoolError::other(format!("Failed to serialize config: {}", e)))?;
+
+  fs::write(&config_path, content)
+    .map_err(|e| SilkSpoolError::io("write config file", &config_path, e))?;
+
+  Ok(())
+}
+
+/// Validate a game path
+pub fn validate_game_path(path: &Path) -> Result<()> {
+  let invalid = |reason: &str| SilkSpoolError::InvalidGamePath {
+    path: path.to_path_buf(),
+    reason: reason.to_string(),
+  };
+
+  if !path.exists() {
+    return Err(invalid("Path does not exist"));
+  }
+
+  if !path.is_dir() {
+    return Err(invalid("Path is not a directory"));
+  }
+
+  // Check if this looks like a valid game installation
+  let is_valid = is_valid_game_directory(path);
+
+  if !is_valid {
+    return Err(invalid(
+      "Directory does not appear to contain a valid game installation",
+    ));
+  }
+
+  Ok(())
+}
+
+/// Check if a directory contains a valid game installation
+#[cfg_attr(
+  not(any(target_os = "windows", target_os = "macos")),
+  allow(unused_variables)
+)]
+fn is_valid_game_directory(path: &Path) -> bool {
+  #[cfg(target_os = "windows")]
+  {
+    // Look for .exe files in the directory
+    if let Ok(entries) = fs::read_dir(path) {
+      for entry in entries {
+        if let Ok(entry) = entry {
+          if entry.file_type().map(|ft| ft.is_file()).unwrap_or(false) {
+            if let Some(extension) = entry.path().extension() {
+              if extension == "exe" {
+                return true;
+              }
+            }
+          }
+        }
+      }
+    }
+  }
+
+  #[cfg(target_os = "macos")]
+  {
+    // Look for .app bundles in the directory
+    if let Ok(entries) = fs::read_dir(path) {
+      for entry in entries {
+        if let Ok(entry) = entry {
+          if entry.file_type().map(|ft| ft.is_dir()).unwrap_or(false) {
+            if let Some(extension) = entry.path().extension() {
+              if extension == "app" {
+                return true;
+              }
+            }
+          }
+        }
+      }
+    }
+  }
+
+  false
+}
+
+/// Add a repository to the configuration, with the lowest priority
+pub fn add_repo(entry: RepoEntry) -> Result<()> {
+  let mut config = load_config()?;
+
+  if config.repos.iter().any(|repo| repo.url == entry.url) {
+    return Ok(());
+  }
+
+  let priority = config
+    .repos
+    .iter()
+    .map(|repo| repo.priority + 1)
+    .max()
+    .unwrap_or(0);
+  config.repos.push(RepoEntry { priority, ..entry });
+  save_config(&config)
+}
+
+/// Remove a repository from the configuration
+pub fn remove_repo(url: &str) -> Result<()> {
+  let mut config = load_config()?;
+
+  config.repos.retain(|repo| repo.url != url);
+  save_config(&config)?;
+
+  Ok(())
+}
+
+/// Replace the settings of a configured repository
+pub fn update_repo(entry: RepoEntry) -> Result<()> {
+  let mut config = load_config()?;
+
+  let repo = config
+    .repos
+    .iter_mut()
+    .find(|repo| repo.url == entry.url)
+    .ok_or_else(|| SilkSpoolError::RepositoryNotConfigured {
+      url: entry.url.clone(),
+    })?;
+  *repo = entry;
+
+  save_config(&config)
+}
+
+/// Set repository priorities from an ordered list of URLs, highest priority first
+pub fn reorder_repos(urls: &[String]) -> Result<()> {
+  let mut config = load_config()?;
+  config.reorder_repos(urls);
+  save_config(&config)
+}
+
+/// Get the configuration of a repository
+pub fn find_repo(url: &str) -> Option<RepoEntry> {
+  load_config()
+    .ok()?
+    .repos
+    .into_iter()
+    .find(|repo| repo.url == url)
+}
+
+/// Update the game path in the configuration
+pub fn update_game_path(path: Option<PathBuf>) -> Result<()> {
+  let mut config = load_config()?;
+
+  if let Some(ref game_path) = path {
+    validate_game_path(game_path)?;
+  }
+
+  config.game_path = path;
+  save_config(&config)?;
+
+  Ok(())
+}
+
+/// Turn offline mode on or off
+pub fn set_offline_mode(enabled: bool) -> Result<()> {
+  let mut config = load_config()?;
+  config.offline_mode = enabled;
+  save_config(&config)
+}
+
+/// Check whether offline mode is on, treating an unreadable config as online
+pub fn is_offline() -> bool {
+  load_config()
+    .map(|config| config.offline_mode)
+    .unwrap_or(false)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_config_serialization() {
+    let config = AppConfig::default();
+    let json = serde_json::to_string(&config).unwrap();
+    let deserialized: AppConfig = serde_json::from_str(&json).unwrap();
+
+    assert_eq!(config.game_path, deserialized.game_path);
+    assert_eq!(config.repos, deserialized.repos);
+    assert_eq!(config.ui.theme, deserialized.ui.theme);
+  }
+
+  #[test]
+  fn test_repo_management() {
+    let mut config = AppConfig::default();
+    config
+      .repos
+      .push(RepoEntry::new("https://example.com/repo1".to_string()));
+    config.repos.push(RepoEntry {
+      priority: 1,
+      ..RepoEntry::new("https://example.com/repo2".to_string())
+    });
+    config.repos.push(RepoEntry {
+      priority: 2,
+      enabled: false,
+      ..RepoEntry::new("https://example.com/repo3".to_string())
+    });
+
+    config.reorder_repos(&["https://example.com/repo2".to_string()]);
+
+    let urls: Vec<&str> = config.repos.iter().map(|repo| repo.url.as_str()).collect();
+    assert_eq!(
+      urls,
+      vec![
+        "https://example.com/repo2",
+        "https://example.com/repo1",
+        "https://example.com/repo3",
+      ]
+    );
+    assert_eq!(config.repos[1].priority, 1);
+    assert_eq!(config.enabled_repos().len(), 2);
+  }
+
+  #[test]
+  fn test_old_repo_list_is_migrated() {
+    let json = r#"{
+      "game_path": null,
+      "repos": ["https://example.com/a.json", {"url": "https://example.com/b.json", "priority": 5}],
+      "ui": {"theme": "dark", "window_size": {"width": 1200, "height": 800}}
+    }"#;
+
+    let config: AppConfig = serde_json::from_str(json).unwrap();
+
+    assert_eq!(
+      config.repos[0],
+      RepoEntry::new("https://example.com/a.json".to_string())
+    );
+    assert_eq!(config.repos[1].priority, 5);
+    assert!(config.repos[1].enabled);
+  }
+
+  #[test]
+  fn test_debug_output_hides_credentials() {
+    let entry = RepoEntry {
+      auth: Some(RepoAuth::Basic {
+        username: "modder".to_string(),
+        password: "hunter2".to_string(),
+      }),
+      ..RepoEntry::new("https://example.com/a.json".to_string())
+    };
+    let bearer = RepoAuth::Bearer {
+      token: "secret-token".to_string(),
+    };
+
+    let debug = format!("{:?} {:?}", entry, bearer);
+    assert!(debug.contains("modder"));
+    assert!(!debug.contains("hunter2"));
+    assert!(!debug.contains("secret-token"));
+  }
+}
@@ -0,0 +1,257 @@
+use std::{
+  collections::{BTreeMap, HashSet},
+  path::Path,
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::types::{InstalledMod, InstalledModsData};
+
+/// What to do when a mod would install files that another mod already owns
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConflictPolicy {
+  /// Refuse to install and report the clashing files
+  #[default]
+  Abort,
+  /// Replace the existing files and transfer their ownership to the new mod
+  Overwrite,
+  /// Leave the existing files alone and install the new ones under a mod-specific name
+  KeepBoth,
+}
+
+/// A file claimed by more than one mod
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FileConflict {
+  pub path: String,
+  pub owners: Vec<String>,
+}
+
+/// Maps every tracked file to the mods that claim it
+#[derive(Debug, Default)]
+pub struct FileOwnershipIndex {
+  owners: BTreeMap<String, (String, Vec<String>)>,
+}
+
+impl FileOwnershipIndex {
+  /// Build the index from the `installed_files` of every enabled mod
+  ///
+  /// Disabled mods are left out because their files are not in place; `enable_mod` checks
+  /// them against the index before moving them back.
+  pub fn build(mods: &[InstalledMod]) -> Self {
+    let mut index = Self::default();
+
+    for installed_mod in mods.iter().filter(|m| m.enabled) {
+      for file in &installed_mod.installed_files {
+        let (_, owners) = index
+          .owners
+          .entry(normalize_path(file))
+          .or_insert_with(|| (file.clone(), Vec::new()));
+
+        if !owners.contains(&installed_mod.mod_id) {
+          owners.push(installed_mod.mod_id.clone());
+        }
+      }
+    }
+
+    index
+  }
+
+  /// Get the IDs of the mods that own a file
+  pub fn owners_of(&self, path: &str) -> &[String] {
+    self
+      .owners
+      .get(&normalize_path(path))
+      .map(|(_, owners)| owners.as_slice())
+      .unwrap_or(&[])
+  }
+
+  /// List the files that `mod_id` would clash on if it installed `files`
+  pub fn find_conflicts(&self, files: &[String], mod_id: &str) -> Vec<FileConflict> {
+    files
+      .iter()
+      .filter_map(|file| {
+        let owners: Vec<String> = self
+          .owners_of(file)
+          .iter()
+          .filter(|owner| *owner != mod_id)
+          .cloned()
+          .collect();
+
+        if owners.is_empty() {
+          None
+        } else {
+          Some(FileConflict {
+            path: file.clone(),
+            owners,
+          })
+        }
+      })
+      .collect()
+  }
+
+  /// List every file that is currently claimed by more than one mod
+  pub fn current_conflicts(&self) -> Vec<FileConflict> {
+    self
+      .owners
+      .values()
+      .filter(|(_, owners)| owners.len() > 1)
+      .map(|(path, owners)| FileConflict {
+        path: path.clone(),
+        owners: owners.clone(),
+      })
+      .collect()
+  }
+
+  /// Check whether a directory contains files owned by a mod other than `mod_id`
+  pub fn has_foreign_files_under(&self, dir: &Path, mod_id: &str) -> bool {
+    let prefix = format!(
+      "{}/",
+      normalize_path(&dir.to_string_lossy()).trim_end_matches('/')
+    );
+
+    self.owners.iter().any(|(path, (_, owners))| {
+      path.starts_with(&prefix) && owners.iter().any(|owner| owner != mod_id)
+    })
+  }
+}
+
+/// Normalize a path so the same file always maps to the same index key
+fn normalize_path(path: &str) -> String {
+  let normalized = path.replace('\\', "/");
+
+  // Windows and macOS file systems are case-insensitive by default
+  if cfg!(any(target_os = "windows", target_os = "macos")) {
+    normalized.to_lowercase()
+  } else {
+    normalized
+  }
+}
+
+/// Get the path a file is installed under when both copies are kept
+pub fn keep_both_path(path: &Path, mod_id: &str) -> std::path::PathBuf {
+  let stem = path
+    .file_stem()
+    .map(|s| s.to_string_lossy().to_string())
+    .unwrap_or_default();
+
+  let file_name = match path.extension() {
+    Some(ext) => format!("{}.{}.{}", stem, mod_id, ext.to_string_lossy()),
+    None => format!("{}.{}", stem, mod_id),
+  };
+
+  path.with_file_name(file_name)
+}
+
+/// Remove overwritten files from the mods that previously owned them
+pub fn transfer_ownership(data: &mut InstalledModsData, files: &[String], new_owner: &str) {
+  let transferred: HashSet<String> = files.iter().map(|f| normalize_path(f)).collect();
+
+  for installed_mod in data.mods.iter_mut() {
+    if installed_mod.mod_id == new_owner || !installed_mod.enabled {
+      continue;
+    }
+
+    installed_mod
+      .installed_files
+      .retain(|file| !transferred.contains(&normalize_path(file)));
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn installed(mod_id: &str, files: &[&str]) -> InstalledMod {
+    InstalledMod {
+      mod_id: mod_id.to_string(),
+      mod_title: mod_id.to_string(),
+      version: "1.0.0".to_string(),
+      installed_at: "2024-01-01T00:00:00Z".to_string(),
+      installed_files: files.iter().map(|f| f.to_string()).collect(),
+      game_path: "/game".to_string(),
+      download_url: None,
+      enabled: true,
+      pinned_version: None,
+      history: vec![],
+    }
+  }
+
+  #[test]
+  fn test_find_conflicts_ignores_own_files() {
+    let index = FileOwnershipIndex::build(&[
+      installed("a", &["/game/BepInEx/plugins/Shared.dll"]),
+      installed("b", &["/game/BepInEx/plugins/B/B.dll"]),
+    ]);
+
+    let files = vec![
+      "/game/BepInEx/plugins/Shared.dll".to_string(),
+      "/game/BepInEx/plugins/B/B.dll".to_string(),
+    ];
+
+    let conflicts = index.find_conflicts(&files, "b");
+    assert_eq!(conflicts.len(), 1);
+    assert_eq!(conflicts[0].owners, vec!["a".to_string()]);
+  }
+
+  #[test]
+  fn test_current_conflicts() {
+    let index = FileOwnershipIndex::build(&[
+      installed("a", &["/game/BepInEx/plugins/Shared.dll", "/game/a.txt"]),
+      installed("b", &["/game/BepInEx/plugins/Shared.dll"]),
+    ]);
+
+    let conflicts = index.current_conflicts();
+    assert_eq!(conflicts.len(), 1);
+    assert_eq!(conflicts[0].path, "/game/BepInEx/plugins/Shared.dll");
+    assert_eq!(conflicts[0].owners.len(), 2);
+  }
+
+  #[test]
+  fn test_disabled_mods_are_not_indexed() {
+    let mut disabled = installed("a", &["/game/BepInEx/plugins/Shared.dll"]);
+    disabled.enabled = false;
+    let index = FileOwnershipIndex::build(&[disabled]);
+
+    assert!(index
+      .owners_of("/game/BepInEx/plugins/Shared.dll")
+      .is_empty());
+  }
+
+  #[test]
+  fn test_has_foreign_files_under() {
+    let index = FileOwnershipIndex::build(&[installed("a", &["/game/plugins/Mod/a.dll"])]);
+
+    assert!(index.has_foreign_files_under(Path::new("/game/plugins/Mod"), "b"));
+    assert!(!index.has_foreign_files_under(Path::new("/game/plugins/Mod"), "a"));
+    assert!(!index.has_foreign_files_under(Path::new("/game/plugins/Mo"), "b"));
+  }
+
+  #[test]
+  fn test_transfer_ownership() {
+    let mut data = InstalledModsData {
+      mods: vec![
+        installed("a", &["/game/Shared.dll", "/game/a.txt"]),
+        installed("b", &["/game/Shared.dll"]),
+      ],
+      last_updated: String::new(),
+    };
+
+    transfer_ownership(&mut data, &["/game/Shared.dll".to_string()], "b");
+
+    assert_eq!(
+      data.mods[0].installed_files,
+      vec!["/game/a.txt".to_string()]
+    );
+    assert_eq!(
+      data.mods[1].installed_files,
+      vec!["/game/Shared.dll".to_string()]
+    );
+  }
+
+  #[test]
+  fn test_keep_both_path() {
+    let path = keep_both_path(Path::new("/game/plugins/Shared.dll"), "b");
+    assert_eq!(path, Path::new("/game/plugins/Shared.b.dll"));
+  }
+}
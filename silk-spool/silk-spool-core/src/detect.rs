@@ -2,6 +2,8 @@ use std::path::{Path, PathBuf};
 
 use serde::{Deserialize, Serialize};
 
+use crate::error::{Result, SilkSpoolError};
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BepInExStatus {
   pub present: bool,
@@ -92,6 +94,10 @@ pub fn detect_bepinex_macos(game_path: &Path) -> BepInExStatus {
 }
 
 /// Detect BepInEx installation based on platform
+#[cfg_attr(
+  not(any(target_os = "windows", target_os = "macos")),
+  allow(unused_variables)
+)]
 pub fn detect_bepinex(game_path: &Path) -> BepInExStatus {
   #[cfg(target_os = "windows")]
   {
@@ -114,7 +120,8 @@ pub fn detect_bepinex(game_path: &Path) -> BepInExStatus {
 }
 
 /// Get the current game status by detecting Steam installation and BepInEx
-pub fn get_game_status() -> Result<GameStatus, String> {
+#[tracing::instrument]
+pub fn get_game_status() -> Result<GameStatus> {
   use crate::steam::find_silksong_installation;
 
   match find_silksong_installation() {
@@ -122,6 +129,11 @@ pub fn get_game_status() -> Result<GameStatus, String> {
       if detection_result.found {
         let game_path = detection_result.path.unwrap();
         let bepinex_status = detect_bepinex(&game_path);
+        tracing::info!(
+          path = %game_path.display(),
+          bepinex = bepinex_status.present,
+          "Detected game"
+        );
 
         Ok(GameStatus {
           path: Some(game_path),
@@ -129,6 +141,10 @@ pub fn get_game_status() -> Result<GameStatus, String> {
           bepinex: bepinex_status,
         })
       } else {
+        tracing::info!(
+          libraries = detection_result.libraries.len(),
+          "Game not found in Steam libraries"
+        );
         Ok(GameStatus {
           path: None,
           found: false,
@@ -140,36 +156,48 @@ pub fn get_game_status() -> Result<GameStatus, String> {
         })
       }
     }
-    Err(e) => Ok(GameStatus {
-      path: None,
-      found: false,
-      bepinex: BepInExStatus {
-        present: false,
-        initialized: false,
-        message: format!("Detection error: {}", e),
-      },
-    }),
+    Err(e) => {
+      tracing::warn!(error = %e, "Game detection failed");
+      Ok(GameStatus {
+        path: None,
+        found: false,
+        bepinex: BepInExStatus {
+          present: false,
+          initialized: false,
+          message: format!("Detection error: {}", e),
+        },
+      })
+    }
   }
 }
 
 /// Validate a manually selected game path
-pub fn validate_game_path(path: &Path) -> Result<GameStatus, String> {
+#[tracing::instrument(err)]
+pub fn validate_game_path(path: &Path) -> Result<GameStatus> {
+  let invalid = |reason: &str| SilkSpoolError::InvalidGamePath {
+    path: path.to_path_buf(),
+    reason: reason.to_string(),
+  };
+
   if !path.exists() {
-    return Err("Path does not exist".to_string());
+    return Err(invalid("Path does not exist"));
   }
 
   if !path.is_dir() {
-    return Err("Path is not a directory".to_string());
+    return Err(invalid("Path is not a directory"));
   }
 
   // Check if this looks like a valid game installation
   let is_valid = is_valid_game_directory(path);
 
   if !is_valid {
-    return Err("Directory does not appear to contain a valid game installation".to_string());
+    return Err(invalid(
+      "Directory does not appear to contain a valid game installation",
+    ));
   }
 
   let bepinex_status = detect_bepinex(path);
+  tracing::info!(bepinex = bepinex_status.present, "Validated game path");
 
   Ok(GameStatus {
     path: Some(path.to_path_buf()),
@@ -179,6 +207,10 @@ pub fn validate_game_path(path: &Path) -> Result<GameStatus, String> {
 }
 
 /// Check if a directory contains a valid game installation
+#[cfg_attr(
+  not(any(target_os = "windows", target_os = "macos")),
+  allow(unused_variables)
+)]
 fn is_valid_game_directory(path: &Path) -> bool {
   #[cfg(target_os = "windows")]
   {
@@ -0,0 +1,276 @@
+use std::{
+  fmt,
+  path::{Path, PathBuf},
+};
+
+use serde::{ser::SerializeMap, Serialize, Serializer};
+use thiserror::Error;
+
+/// Result type used across the backend
+pub type Result<T, E = SilkSpoolError> = std::result::Result<T, E>;
+
+/// Everything that can go wrong in the backend
+///
+/// Every variant has a stable `code` so callers can react to a specific failure, such as offering
+/// to install BepInEx, without matching on the message text.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum SilkSpoolError {
+  /// No game installation could be found
+  #[error("Game not found: {reason}")]
+  GameNotFound { reason: String },
+
+  /// A directory given as the game path does not hold the game
+  #[error("Invalid game directory {}: {reason}", path.display())]
+  InvalidGamePath { path: PathBuf, reason: String },
+
+  /// The game has no BepInEx to load mods with
+  #[error("BepInEx not found in {}. Please install BepInEx first.", path.display())]
+  BepInExMissing { path: PathBuf },
+
+  /// The network is needed while offline mode is enabled
+  #[error("Offline mode is enabled, cannot fetch {url}")]
+  Offline { url: String },
+
+  /// A request failed before the server answered
+  #[error("Failed to fetch {url}: {message}")]
+  Network { url: String, message: String },
+
+  /// The server answered with an error status
+  #[error("Request to {url} failed with status {status}")]
+  HttpStatus { url: String, status: u16 },
+
+  /// A download does not match the checksum it was recorded with
+  #[error("Checksum mismatch for {mod_id}: expected {expected}, got {actual}")]
+  ChecksumMismatch {
+    mod_id: String,
+    expected: String,
+    actual: String,
+  },
+
+  /// Reading or writing a file failed
+  #[error("Failed to {action} {}: {message}", path.display())]
+  Io {
+    action: String,
+    path: PathBuf,
+    message: String,
+  },
+
+  /// A file or response is not in the expected format
+  #[error("Failed to parse {what}: {message}")]
+  Parse { what: String, message: String },
+
+  /// A downloaded archive could not be unpacked
+  #[error("Failed to extract {}: {message}", path.display())]
+  Archive { path: PathBuf, message: String },
+
+  /// A repository breaks the repository format or cannot be used
+  #[error("{message}")]
+  InvalidRepository { message: String },
+
+  /// A repository with a pinned key whose signature is missing or does not match
+  #[error("Signature check failed for {url}: {message}")]
+  SignatureMismatch { url: String, message: String },
+
+  /// A repository URL that is not in the configuration
+  #[error("Repository {url} is not configured")]
+  RepositoryNotConfigured { url: String },
+
+  /// A mod that is not installed or not in any repository
+  #[error("Mod with ID {mod_id} not found")]
+  ModNotFound { mod_id: String },
+
+  /// There is nothing to download for the version of a mod that was asked for
+  #[error("{message}")]
+  DownloadUnavailable { mod_id: String, message: String },
+
+  /// Dependencies cannot be resolved
+  #[error("{message}")]
+  Dependency {
+    mod_id: Option<String>,
+    message: String,
+  },
+
+  /// Mods that declare each other as incompatible would end up installed together
+  #[error("{message}")]
+  Incompatible { message: String },
+
+  /// Installing a mod would overwrite files owned by other mods
+  #[error("{message}")]
+  FileConflict { mod_id: String, message: String },
+
+  /// A profile name that does not exist
+  #[error("Profile '{name}' not found")]
+  ProfileNotFound { name: String },
+
+  /// A request that can never succeed as given
+  #[error("{message}")]
+  InvalidInput { message: String },
+
+  #[error("{message}")]
+  Other { message: String },
+}
+
+impl SilkSpoolError {
+  /// Build an error for a failed file operation, e.g. `io("read config file", path, e)`
+  pub fn io(action: &str, path: impl AsRef<Path>, error: impl fmt::Display) -> Self {
+    Self::Io {
+      action: action.to_string(),
+      path: path.as_ref().to_path_buf(),
+      message: error.to_string(),
+    }
+  }
+
+  /// Build an error for a request that failed before the server answered
+  pub fn network(url: &str, error: impl fmt::Display) -> Self {
+    Self::Network {
+      url: url.to_string(),
+      message: error.to_string(),
+    }
+  }
+
+  /// Build an error for a document that could not be parsed
+  pub fn parse(what: &str, error: impl fmt::Display) -> Self {
+    Self::Parse {
+      what: what.to_string(),
+      message: error.to_string(),
+    }
+  }
+
+  /// Build an error for an archive that could not be unpacked
+  pub fn archive(path: impl AsRef<Path>, error: impl fmt::Display) -> Self {
+    Self::Archive {
+      path: path.as_ref().to_path_buf(),
+      message: error.to_string(),
+    }
+  }
+
+  pub fn mod_not_found(mod_id: &str) -> Self {
+    Self::ModNotFound {
+      mod_id: mod_id.to_string(),
+    }
+  }
+
+  pub fn invalid_input(message: impl Into<String>) -> Self {
+    Self::InvalidInput {
+      message: message.into(),
+    }
+  }
+
+  pub fn other(message: impl Into<String>) -> Self {
+    Self::Other {
+      message: message.into(),
+    }
+  }
+
+  /// Get the stable identifier of this kind of error
+  pub fn code(&self) -> &'static str {
+    match self {
+      Self::GameNotFound { .. } => "game_not_found",
+      Self::InvalidGamePath { .. } => "invalid_game_path",
+      Self::BepInExMissing { .. } => "bepinex_missing",
+      Self::Offline { .. } => "offline",
+      Self::Network { .. } => "network",
+      Self::HttpStatus { .. } => "http_status",
+      Self::ChecksumMismatch { .. } => "checksum_mismatch",
+      Self::Io { .. } => "io",
+      Self::Parse { .. } => "parse",
+      Self::Archive { .. } => "archive",
+      Self::InvalidRepository { .. } => "invalid_repository",
+      Self::SignatureMismatch { .. } => "signature_mismatch",
+      Self::RepositoryNotConfigured { .. } => "repository_not_configured",
+      Self::ModNotFound { .. } => "mod_not_found",
+      Self::DownloadUnavailable { .. } => "download_unavailable",
+      Self::Dependency { .. } => "dependency",
+      Self::Incompatible { .. } => "incompatible",
+      Self::FileConflict { .. } => "file_conflict",
+      Self::ProfileNotFound { .. } => "profile_not_found",
+      Self::InvalidInput { .. } => "invalid_input",
+      Self::Other { .. } => "other",
+    }
+  }
+
+  /// Get the file or directory the error is about
+  pub fn path(&self) -> Option<&Path> {
+    match self {
+      Self::InvalidGamePath { path, .. }
+      | Self::BepInExMissing { path }
+      | Self::Io { path, .. }
+      | Self::Archive { path, .. } => Some(path),
+      _ => None,
+    }
+  }
+
+  /// Get the URL the error is about
+  pub fn url(&self) -> Option<&str> {
+    match self {
+      Self::Offline { url }
+      | Self::Network { url, .. }
+      | Self::HttpStatus { url, .. }
+      | Self::SignatureMismatch { url, .. }
+      | Self::RepositoryNotConfigured { url } => Some(url),
+      _ => None,
+    }
+  }
+
+  /// Get the ID of the mod the error is about
+  pub fn mod_id(&self) -> Option<&str> {
+    match self {
+      Self::ChecksumMismatch { mod_id, .. }
+      | Self::ModNotFound { mod_id }
+      | Self::DownloadUnavailable { mod_id, .. }
+      | Self::FileConflict { mod_id, .. } => Some(mod_id),
+      Self::Dependency { mod_id, .. } => mod_id.as_deref(),
+      _ => None,
+    }
+  }
+}
+
+/// Errors are sent to the frontend as `{ code, message, path?, url?, mod_id? }`
+impl Serialize for SilkSpoolError {
+  fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    let mut map = serializer.serialize_map(None)?;
+    map.serialize_entry("code", self.code())?;
+    map.serialize_entry("message", &self.to_string())?;
+    if let Some(path) = self.path() {
+      map.serialize_entry("path", &path.to_string_lossy())?;
+    }
+    if let Some(url) = self.url() {
+      map.serialize_entry("url", url)?;
+    }
+    if let Some(mod_id) = self.mod_id() {
+      map.serialize_entry("mod_id", mod_id)?;
+    }
+    map.end()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_errors_serialize_with_code_and_context() {
+    let error = SilkSpoolError::BepInExMissing {
+      path: PathBuf::from("/games/Silksong"),
+    };
+
+    assert_eq!(
+      serde_json::to_value(&error).unwrap(),
+      serde_json::json!({
+        "code": "bepinex_missing",
+        "message": "BepInEx not found in /games/Silksong. Please install BepInEx first.",
+        "path": "/games/Silksong",
+      })
+    );
+
+    let error = SilkSpoolError::ChecksumMismatch {
+      mod_id: "some-mod".to_string(),
+      expected: "aa".to_string(),
+      actual: "bb".to_string(),
+    };
+    let value = serde_json::to_value(&error).unwrap();
+    assert_eq!(value["code"], "checksum_mismatch");
+    assert_eq!(value["mod_id"], "some-mod");
+    assert!(value.get("path").is_none());
+  }
+}
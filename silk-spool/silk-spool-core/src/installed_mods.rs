@@ -0,0 +1,369 @@
+use std::{fs, path::Path};
+
+use crate::{
+  config::get_app_cache_dir,
+  conflicts::{transfer_ownership, FileConflict, FileOwnershipIndex},
+  error::{Result, SilkSpoolError},
+  types::{InstalledMod, InstalledModsData, ModVersionRecord},
+};
+
+/// How many replaced versions are remembered for each mod
+const MAX_VERSION_HISTORY: usize = 3;
+
+/// Get the path to the installed mods data file
+pub fn get_installed_mods_path() -> Result<std::path::PathBuf> {
+  let cache_dir = get_app_cache_dir()?;
+
+  // Create directory if it doesn't exist
+  fs::create_dir_all(&cache_dir)
+    .map_err(|e| SilkSpoolError::io("create cache directory", &cache_dir, e))?;
+
+  Ok(cache_dir.join("installed_mods.json"))
+}
+
+/// Load installed mods from storage
+pub fn load_installed_mods() -> Result<InstalledModsData> {
+  let path = get_installed_mods_path()?;
+
+  if !path.exists() {
+    return Ok(InstalledModsData {
+      mods: Vec::new(),
+      last_updated: chrono::Utc::now().to_rfc3339(),
+    });
+  }
+
+  let content = fs::read_to_string(&path)
+    .map_err(|e| SilkSpoolError::io("read installed mods file", &path, e))?;
+
+  let data: InstalledModsData =
+    serde_json::from_str(&content).map_err(|e| SilkSpoolError::parse("installed mods data", e))?;
+
+  Ok(data)
+}
+
+/// Save installed mods to storage
+pub fn save_installed_mods(data: &InstalledModsData) -> Result<()> {
+  let path = get_installed_mods_path()?;
+
+  let content = serde_json::to_string_pretty(data).map_err(|e| {
+    SilkSpoolError::other(format!("Failed to serialize installed mods data: {}", e))
+  })?;
+
+  fs::write(&path, content)
+    .map_err(|e| SilkSpoolError::io("write installed mods file", &path, e))?;
+
+  Ok(())
+}
+
+/// Check whether a record is the install of `mod_id` in `game_path`
+fn is_install(mod_: &InstalledMod, mod_id: &str, game_path: &Path) -> bool {
+  mod_.mod_id == mod_id && Path::new(&mod_.game_path) == game_path
+}
+
+/// Add a mod to the installed list of its game
+///
+/// Replacing a record keeps its version history and pin, so the replaced version can still be
+/// rolled back to.
+pub fn add_installed_mod(
+  mod_id: String,
+  mod_title: String,
+  version: String,
+  installed_files: Vec<String>,
+  game_path: String,
+  download_url: Option<String>,
+) -> Result<()> {
+  let mut data = load_installed_mods()?;
+
+  // Take out the existing install of this mod in the same game, if any
+  let existing = data
+    .mods
+    .iter()
+    .position(|mod_| is_install(mod_, &mod_id, Path::new(&game_path)))
+    .map(|index| data.mods.remove(index));
+
+  let installed_mod = InstalledMod {
+    mod_id,
+    mod_title,
+    installed_at: chrono::Utc::now().to_rfc3339(),
+    installed_files,
+    game_path,
+    download_url,
+    enabled: true,
+    pinned_version: existing
+      .as_ref()
+      .and_then(|mod_| mod_.pinned_version.as_ref())
+      .map(|_| version.clone()),
+    history: existing.map(|mod_| mod_.history).unwrap_or_default(),
+    version,
+  };
+
+  data.mods.push(installed_mod);
+  data.last_updated = chrono::Utc::now().to_rfc3339();
+
+  save_installed_mods(&data)
+}
+
+/// Remove a mod from the installed list of a game
+pub fn remove_installed_mod(mod_id: &str, game_path: &Path) -> Result<()> {
+  let mut data = load_installed_mods()?;
+
+  data
+    .mods
+    .retain(|mod_| !is_install(mod_, mod_id, game_path));
+  data.last_updated = chrono::Utc::now().to_rfc3339();
+
+  save_installed_mods(&data)
+}
+
+/// Get the install of a mod in a game
+pub fn get_installed_mod(mod_id: &str, game_path: &Path) -> Result<Option<InstalledMod>> {
+  let data = load_installed_mods()?;
+  Ok(
+    data
+      .mods
+      .into_iter()
+      .find(|mod_| is_install(mod_, mod_id, game_path)),
+  )
+}
+
+/// Check if a mod is installed in a game
+pub fn is_mod_installed(mod_id: &str, game_path: &Path) -> Result<bool> {
+  let data = load_installed_mods()?;
+  Ok(
+    data
+      .mods
+      .iter()
+      .any(|mod_| is_install(mod_, mod_id, game_path)),
+  )
+}
+
+/// Get all installed mods
+pub fn get_all_installed_mods() -> Result<Vec<InstalledMod>> {
+  let data = load_installed_mods()?;
+  Ok(data.mods)
+}
+
+/// Get the mods installed into one game directory
+pub fn get_installed_mods_for_game(game_path: &Path) -> Result<Vec<InstalledMod>> {
+  let data = load_installed_mods()?;
+  Ok(
+    data
+      .mods
+      .into_iter()
+      .filter(|mod_| Path::new(&mod_.game_path) == game_path)
+      .collect(),
+  )
+}
+
+/// Update mod version after reinstallation
+pub fn update_mod_version(
+  mod_id: &str,
+  game_path: &Path,
+  new_version: String,
+  new_installed_files: Vec<String>,
+) -> Result<()> {
+  let mut data = load_installed_mods()?;
+
+  if let Some(mod_) = data
+    .mods
+    .iter_mut()
+    .find(|mod_| is_install(mod_, mod_id, game_path))
+  {
+    // The old files are already gone, but the version can still be reinstalled from its URL
+    mod_.history.push(ModVersionRecord {
+      version: std::mem::replace(&mut mod_.version, new_version),
+      installed_files: std::mem::replace(&mut mod_.installed_files, new_installed_files),
+      download_url: mod_.download_url.clone(),
+      replaced_at: chrono::Utc::now().to_rfc3339(),
+      archive_path: None,
+    });
+    trim_version_history(&mut mod_.history);
+    mod_.installed_at = chrono::Utc::now().to_rfc3339();
+
+    data.last_updated = chrono::Utc::now().to_rfc3339();
+    save_installed_mods(&data)
+  } else {
+    Err(SilkSpoolError::mod_not_found(mod_id))
+  }
+}
+
+/// Mark a mod as enabled or disabled
+pub fn set_mod_enabled(mod_id: &str, game_path: &Path, enabled: bool) -> Result<()> {
+  let mut data = load_installed_mods()?;
+
+  if let Some(mod_) = data
+    .mods
+    .iter_mut()
+    .find(|mod_| is_install(mod_, mod_id, game_path))
+  {
+    mod_.enabled = enabled;
+
+    data.last_updated = chrono::Utc::now().to_rfc3339();
+    save_installed_mods(&data)
+  } else {
+    Err(SilkSpoolError::mod_not_found(mod_id))
+  }
+}
+
+/// Hold a mod at its current version, or release the hold
+pub fn set_mod_pinned(mod_id: &str, game_path: &Path, pinned: bool) -> Result<()> {
+  let mut data = load_installed_mods()?;
+
+  if let Some(mod_) = data
+    .mods
+    .iter_mut()
+    .find(|mod_| is_install(mod_, mod_id, game_path))
+  {
+    mod_.pinned_version = pinned.then(|| mod_.version.clone());
+
+    data.last_updated = chrono::Utc::now().to_rfc3339();
+    save_installed_mods(&data)
+  } else {
+    Err(SilkSpoolError::mod_not_found(mod_id))
+  }
+}
+
+/// Put back a previously saved record of an installed mod
+pub fn restore_installed_mod(installed_mod: InstalledMod) -> Result<()> {
+  let mut data = load_installed_mods()?;
+
+  data.mods.retain(|mod_| {
+    !is_install(
+      mod_,
+      &installed_mod.mod_id,
+      Path::new(&installed_mod.game_path),
+    )
+  });
+  data.mods.push(installed_mod);
+  data.last_updated = chrono::Utc::now().to_rfc3339();
+
+  save_installed_mods(&data)
+}
+
+/// Replace the version history of an installed mod
+pub fn set_version_history(
+  mod_id: &str,
+  game_path: &Path,
+  mut history: Vec<ModVersionRecord>,
+) -> Result<()> {
+  let mut data = load_installed_mods()?;
+
+  if let Some(mod_) = data
+    .mods
+    .iter_mut()
+    .find(|mod_| is_install(mod_, mod_id, game_path))
+  {
+    trim_version_history(&mut history);
+    mod_.history = history;
+
+    data.last_updated = chrono::Utc::now().to_rfc3339();
+    save_installed_mods(&data)
+  } else {
+    Err(SilkSpoolError::mod_not_found(mod_id))
+  }
+}
+
+/// Drop the oldest history entries past the limit, together with their archived files
+fn trim_version_history(history: &mut Vec<ModVersionRecord>) {
+  let excess = history.len().saturating_sub(MAX_VERSION_HISTORY);
+
+  for dropped in history.drain(..excess) {
+    if let Some(archive_path) = dropped.archive_path {
+      let _ = fs::remove_dir_all(archive_path);
+    }
+  }
+}
+
+/// Clear all installed mods (for testing or reset)
+pub fn clear_all_installed_mods() -> Result<()> {
+  let data = InstalledModsData {
+    mods: Vec::new(),
+    last_updated: chrono::Utc::now().to_rfc3339(),
+  };
+
+  save_installed_mods(&data)
+}
+
+/// Get installed mods count
+pub fn get_installed_mods_count() -> Result<usize> {
+  let data = load_installed_mods()?;
+  Ok(data.mods.len())
+}
+
+/// Drop overwritten files from the mods that previously owned them
+pub fn transfer_file_ownership(files: &[String], new_owner: &str) -> Result<()> {
+  let mut data = load_installed_mods()?;
+
+  transfer_ownership(&mut data, files, new_owner);
+  data.last_updated = chrono::Utc::now().to_rfc3339();
+
+  save_installed_mods(&data)
+}
+
+/// Get every file currently claimed by more than one installed mod
+pub fn get_file_conflicts() -> Result<Vec<FileConflict>> {
+  let data = load_installed_mods()?;
+  Ok(FileOwnershipIndex::build(&data.mods).current_conflicts())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn record(version: &str, archive_path: Option<String>) -> ModVersionRecord {
+    ModVersionRecord {
+      version: version.to_string(),
+      installed_files: vec![],
+      download_url: None,
+      replaced_at: "2024-01-01T00:00:00Z".to_string(),
+      archive_path,
+    }
+  }
+
+  #[test]
+  fn test_add_installed_mod_keeps_history_and_pin() {
+    let _dirs = crate::config::test_dirs::isolate();
+    let add = |version: &str| {
+      add_installed_mod(
+        "a".to_string(),
+        "A".to_string(),
+        version.to_string(),
+        vec![],
+        "/game".to_string(),
+        None,
+      )
+      .unwrap()
+    };
+    let game_path = Path::new("/game");
+
+    add("1.0.0");
+    set_mod_pinned("a", game_path, true).unwrap();
+    set_version_history("a", game_path, vec![record("0.9.0", None)]).unwrap();
+    add("1.1.0");
+
+    let replaced = get_installed_mod("a", game_path).unwrap().unwrap();
+    assert_eq!(replaced.version, "1.1.0");
+    assert_eq!(replaced.pinned_version.as_deref(), Some("1.1.0"));
+    assert_eq!(replaced.history[0].version, "0.9.0");
+    assert_eq!(get_installed_mods_count().unwrap(), 1);
+  }
+
+  #[test]
+  fn test_trim_version_history_removes_old_archives() {
+    let archive = tempfile::tempdir().unwrap();
+    let oldest = archive.path().join("0.1.0");
+    fs::create_dir_all(&oldest).unwrap();
+
+    let mut history = vec![
+      record("0.1.0", Some(oldest.to_string_lossy().to_string())),
+      record("0.2.0", None),
+      record("0.3.0", None),
+      record("0.4.0", None),
+    ];
+    trim_version_history(&mut history);
+
+    let versions: Vec<&str> = history.iter().map(|r| r.version.as_str()).collect();
+    assert_eq!(versions, vec!["0.2.0", "0.3.0", "0.4.0"]);
+    assert!(!oldest.exists());
+  }
+}
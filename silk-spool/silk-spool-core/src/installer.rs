@@ -0,0 +1,1602 @@
+use std::{
+  collections::HashSet,
+  fs, io,
+  path::{Path, PathBuf},
+};
+
+use flate2::read::GzDecoder;
+use sha2::{Digest, Sha256};
+use tar::Archive;
+use tempfile::TempDir;
+use zip::ZipArchive;
+
+use crate::{
+  config::{get_app_cache_dir, is_offline},
+  conflicts::{keep_both_path, ConflictPolicy, FileConflict, FileOwnershipIndex},
+  error::{Result, SilkSpoolError},
+  installed_mods::{
+    add_installed_mod, get_all_installed_mods, get_installed_mod, load_installed_mods,
+    remove_installed_mod, restore_installed_mod, set_mod_enabled, set_mod_pinned,
+    set_version_history, transfer_file_ownership,
+  },
+  types::{InstallResult, InstalledMod, InstalledModEntry, ModVersionRecord},
+};
+
+/// File type detection
+#[derive(Debug, Clone, PartialEq)]
+enum FileType {
+  Zip,
+  TarGz,
+  Dll,
+  Directory,
+  Other,
+}
+
+/// A download URL together with the local repository that lists it
+///
+/// `file://` URLs are only read for mods of a local repository, and only from inside the
+/// repository's directory.
+#[derive(Debug, Clone, Default)]
+pub struct DownloadSource {
+  pub url: String,
+  /// Directory of the local repository that lists the download, if it is local
+  pub local_root: Option<PathBuf>,
+}
+
+impl DownloadSource {
+  /// A download listed by a remote repository, or by none at all
+  pub fn remote(url: &str) -> Self {
+    Self {
+      url: url.to_string(),
+      local_root: None,
+    }
+  }
+
+  /// Get the file to copy for a `file://` URL, or `None` for URLs to download
+  fn local_path(&self) -> Result<Option<PathBuf>> {
+    let Some(parsed) = reqwest::Url::parse(&self.url)
+      .ok()
+      .filter(|parsed| parsed.scheme() == "file")
+    else {
+      return Ok(None);
+    };
+
+    let refuse = |reason: &str| {
+      Err(SilkSpoolError::invalid_input(format!(
+        "Refusing to read {}: {}",
+        self.url, reason
+      )))
+    };
+    let Some(root) = &self.local_root else {
+      return refuse("only local repositories may list file:// downloads");
+    };
+
+    // Resolve links and `..` so the check sees where the file really is
+    let path = parsed
+      .to_file_path()
+      .ok()
+      .and_then(|p| p.canonicalize().ok());
+    match (path, root.canonicalize().ok()) {
+      (Some(path), Some(root)) if path.starts_with(&root) => Ok(Some(path)),
+      _ => refuse("the file is not inside the repository's directory"),
+    }
+  }
+}
+
+/// Download a file from URL to a temporary location
+#[tracing::instrument(skip(temp_path))]
+pub async fn download_file(source: &DownloadSource, temp_path: &Path) -> Result<()> {
+  // Files from local repositories are copied rather than downloaded
+  if let Some(local_path) = source.local_path()? {
+    fs::copy(&local_path, temp_path).map_err(|e| SilkSpoolError::io("copy", &local_path, e))?;
+    return Ok(());
+  }
+
+  let url = source.url.as_str();
+  if is_offline() {
+    return Err(SilkSpoolError::Offline {
+      url: url.to_string(),
+    });
+  }
+
+  let response = reqwest::get(url)
+    .await
+    .map_err(|e| SilkSpoolError::network(url, e))?;
+
+  if !response.status().is_success() {
+    return Err(SilkSpoolError::HttpStatus {
+      url: url.to_string(),
+      status: response.status().as_u16(),
+    });
+  }
+
+  let content_length = response.content_length();
+  tracing::info!(bytes = content_length, "Downloading");
+  let mut downloaded: u64 = 0;
+  let mut stream = response.bytes_stream();
+  let mut file =
+    fs::File::create(temp_path).map_err(|e| SilkSpoolError::io("create file", temp_path, e))?;
+
+  use std::io::Write;
+
+  use futures_util::StreamExt;
+
+  while let Some(chunk) = stream.next().await {
+    let chunk = chunk.map_err(|e| SilkSpoolError::network(url, e))?;
+    file
+      .write_all(&chunk)
+      .map_err(|e| SilkSpoolError::io("write", temp_path, e))?;
+
+    downloaded += chunk.len() as u64;
+
+    // Log progress if we know the total size
+    if let Some(total) = content_length {
+      let progress = (downloaded as f64 / total as f64 * 100.0) as u32;
+      if progress % 10 == 0 && progress > 0 {
+        tracing::debug!(progress, "Download progress");
+      }
+    }
+  }
+
+  tracing::info!(bytes = downloaded, "Download finished");
+  Ok(())
+}
+
+/// Determine file extension from URL for initial naming
+fn download_extension(url: &str) -> &'static str {
+  if url.contains(".zip") {
+    "zip"
+  } else if url.contains(".tar.gz") || url.contains(".tgz") {
+    "tar.gz"
+  } else if url.contains(".dll") {
+    "dll"
+  } else {
+    "bin" // Generic binary file
+  }
+}
+
+/// Get the directory where downloaded mod files are cached
+pub fn get_download_cache_dir() -> Result<PathBuf> {
+  Ok(get_app_cache_dir()?.join("downloads"))
+}
+
+/// Get the cached copy of a download, fetching it first if it is not cached yet
+pub async fn fetch_cached_download(source: &DownloadSource) -> Result<PathBuf> {
+  // A cached copy must not let a source read a file it could not read itself
+  source.local_path()?;
+  let url = source.url.as_str();
+
+  let cache_dir = get_download_cache_dir()?;
+  fs::create_dir_all(&cache_dir)
+    .map_err(|e| SilkSpoolError::io("create download cache directory", &cache_dir, e))?;
+
+  let cached_file = cache_dir.join(format!(
+    "{:x}.{}",
+    Sha256::digest(url.as_bytes()),
+    download_extension(url)
+  ));
+
+  if cached_file.exists() {
+    tracing::debug!(url, "Using cached download");
+    return Ok(cached_file);
+  }
+
+  // Download next to the final file so an interrupted download is never mistaken for a cached one
+  let partial_file = cached_file.with_extension("part");
+  if let Err(e) = download_file(source, &partial_file).await {
+    let _ = fs::remove_file(&partial_file);
+    return Err(e);
+  }
+
+  fs::rename(&partial_file, &cached_file)
+    .map_err(|e| SilkSpoolError::io("store cached download", &cached_file, e))?;
+
+  Ok(cached_file)
+}
+
+/// Compute the SHA-256 checksum of a file as a hex string
+pub fn file_sha256(path: &Path) -> Result<String> {
+  let mut file = fs::File::open(path).map_err(|e| SilkSpoolError::io("open", path, e))?;
+  let mut hasher = Sha256::new();
+
+  io::copy(&mut file, &mut hasher).map_err(|e| SilkSpoolError::io("read", path, e))?;
+
+  Ok(format!("{:x}", hasher.finalize()))
+}
+
+/// Remove every cached download
+pub fn clear_download_cache() -> Result<()> {
+  let cache_dir = get_download_cache_dir()?;
+
+  if cache_dir.exists() {
+    fs::remove_dir_all(&cache_dir)
+      .map_err(|e| SilkSpoolError::io("remove download cache", &cache_dir, e))?;
+  }
+
+  Ok(())
+}
+
+/// Detect file type based on file content and extension
+fn detect_file_type(file_path: &Path) -> Result<FileType> {
+  // First check if it's a directory
+  if file_path.is_dir() {
+    return Ok(FileType::Directory);
+  }
+
+  // Check file extension
+  if let Some(extension) = file_path.extension() {
+    if let Some(ext_str) = extension.to_str() {
+      match ext_str.to_lowercase().as_str() {
+        "dll" => return Ok(FileType::Dll),
+        "zip" => return Ok(FileType::Zip),
+        "gz" => {
+          // Check if it's tar.gz by looking at the filename
+          if let Some(file_name) = file_path.file_name() {
+            if let Some(name_str) = file_name.to_str() {
+              if name_str.contains(".tar.gz") || name_str.contains(".tgz") {
+                return Ok(FileType::TarGz);
+              }
+            }
+          }
+        }
+        _ => {}
+      }
+    }
+  }
+
+  // Try to detect by file content (magic bytes)
+  if let Ok(mut file) = fs::File::open(file_path) {
+    let mut buffer = [0; 4];
+    if io::Read::read(&mut file, &mut buffer).is_ok() {
+      // ZIP files start with PK (0x504B)
+      if buffer[0] == 0x50 && buffer[1] == 0x4B {
+        return Ok(FileType::Zip);
+      }
+      // GZIP files start with 0x1F8B
+      if buffer[0] == 0x1F && buffer[1] == 0x8B {
+        return Ok(FileType::TarGz);
+      }
+    }
+  }
+
+  Ok(FileType::Other)
+}
+
+/// Copy a single file to destination
+fn copy_single_file(source: &Path, dest_dir: &Path, file_name: &str) -> Result<Vec<String>> {
+  let dest_file = dest_dir.join(file_name);
+
+  fs::copy(source, &dest_file).map_err(|e| SilkSpoolError::io("copy file to", &dest_file, e))?;
+
+  Ok(vec![dest_file.to_string_lossy().to_string()])
+}
+
+/// Copy a directory recursively to destination
+fn copy_directory(source: &Path, dest_dir: &Path) -> Result<Vec<String>> {
+  let mut copied_files = Vec::new();
+
+  fn copy_recursive(source: &Path, dest: &Path, copied_files: &mut Vec<String>) -> Result<()> {
+    if source.is_dir() {
+      fs::create_dir_all(dest).map_err(|e| SilkSpoolError::io("create directory", dest, e))?;
+
+      for entry in
+        fs::read_dir(source).map_err(|e| SilkSpoolError::io("read directory", source, e))?
+      {
+        let entry = entry.map_err(|e| SilkSpoolError::io("read directory", source, e))?;
+        let source_path = entry.path();
+        let dest_path = dest.join(entry.file_name());
+
+        if source_path.is_dir() {
+          copy_recursive(&source_path, &dest_path, copied_files)?;
+        } else {
+          fs::copy(&source_path, &dest_path)
+            .map_err(|e| SilkSpoolError::io("copy file to", &dest_path, e))?;
+          copied_files.push(dest_path.to_string_lossy().to_string());
+        }
+      }
+    } else {
+      fs::copy(source, dest).map_err(|e| SilkSpoolError::io("copy file to", dest, e))?;
+      copied_files.push(dest.to_string_lossy().to_string());
+    }
+
+    Ok(())
+  }
+
+  copy_recursive(source, dest_dir, &mut copied_files)?;
+  Ok(copied_files)
+}
+
+/// Extract ZIP archive
+fn extract_zip(archive_path: &Path, extract_to: &Path) -> Result<Vec<String>> {
+  let file =
+    fs::File::open(archive_path).map_err(|e| SilkSpoolError::io("open", archive_path, e))?;
+
+  let mut archive = ZipArchive::new(file).map_err(|e| SilkSpoolError::archive(archive_path, e))?;
+
+  let mut extracted_files = Vec::new();
+
+  for i in 0..archive.len() {
+    let mut file = archive
+      .by_index(i)
+      .map_err(|e| SilkSpoolError::archive(archive_path, e))?;
+
+    let outpath = extract_to.join(file.name());
+
+    // Create parent directories if they don't exist
+    if let Some(parent) = outpath.parent() {
+      fs::create_dir_all(parent).map_err(|e| SilkSpoolError::io("create directory", parent, e))?;
+    }
+
+    if file.name().ends_with('/') {
+      // Directory entry
+      fs::create_dir_all(&outpath)
+        .map_err(|e| SilkSpoolError::io("create directory", &outpath, e))?;
+    } else {
+      // File entry
+      let mut outfile =
+        fs::File::create(&outpath).map_err(|e| SilkSpoolError::io("create file", &outpath, e))?;
+
+      io::copy(&mut file, &mut outfile).map_err(|e| SilkSpoolError::archive(archive_path, e))?;
+
+      extracted_files.push(outpath.to_string_lossy().to_string());
+    }
+  }
+
+  Ok(extracted_files)
+}
+
+/// Extract TAR.GZ archive
+fn extract_tar_gz(archive_path: &Path, extract_to: &Path) -> Result<Vec<String>> {
+  let file =
+    fs::File::open(archive_path).map_err(|e| SilkSpoolError::io("open", archive_path, e))?;
+
+  let gz = GzDecoder::new(file);
+  let mut archive = Archive::new(gz);
+
+  let mut extracted_files = Vec::new();
+
+  archive
+    .entries()
+    .map_err(|e| SilkSpoolError::archive(archive_path, e))?
+    .filter_map(|e| e.ok())
+    .try_for_each(|mut entry| -> Result<()> {
+      let path = entry
+        .path()
+        .map_err(|e| SilkSpoolError::archive(archive_path, e))?;
+
+      let outpath = extract_to.join(path);
+
+      // Create parent directories if they don't exist
+      if let Some(parent) = outpath.parent() {
+        fs::create_dir_all(parent)
+          .map_err(|e| SilkSpoolError::io("create directory", parent, e))?;
+      }
+
+      if entry.header().entry_type().is_dir() {
+        fs::create_dir_all(&outpath)
+          .map_err(|e| SilkSpoolError::io("create directory", &outpath, e))?;
+      } else {
+        let mut outfile =
+          fs::File::create(&outpath).map_err(|e| SilkSpoolError::io("create file", &outpath, e))?;
+
+        io::copy(&mut entry, &mut outfile).map_err(|e| SilkSpoolError::archive(archive_path, e))?;
+
+        extracted_files.push(outpath.to_string_lossy().to_string());
+      }
+
+      Ok(())
+    })?;
+
+  Ok(extracted_files)
+}
+
+/// Find BepInEx plugins directory
+fn find_bepinex_plugins_dir(game_path: &Path) -> Result<PathBuf> {
+  let bepinex_dir = game_path.join("BepInEx");
+  let plugins_dir = bepinex_dir.join("plugins");
+
+  if !bepinex_dir.exists() {
+    return Err(SilkSpoolError::BepInExMissing {
+      path: game_path.to_path_buf(),
+    });
+  }
+
+  if !plugins_dir.exists() {
+    fs::create_dir_all(&plugins_dir)
+      .map_err(|e| SilkSpoolError::io("create plugins directory", &plugins_dir, e))?;
+  }
+
+  Ok(plugins_dir)
+}
+
+/// A downloaded mod unpacked into the staging area
+struct StagedMod {
+  file_type: FileType,
+  /// Pairs of staged file and final destination inside the game directory
+  files: Vec<(PathBuf, PathBuf)>,
+  /// Staging directory of this install, removed when the staged mod is dropped
+  _staging_dir: TempDir,
+}
+
+/// Create a staging directory of its own for one install
+fn create_staging_dir() -> Result<TempDir> {
+  let parent = get_app_cache_dir()
+    .map(|dir| dir.join("staging"))
+    .unwrap_or_else(|_| std::env::temp_dir().join("silk_spool_install"));
+  fs::create_dir_all(&parent)
+    .map_err(|e| SilkSpoolError::io("create staging directory", &parent, e))?;
+
+  tempfile::tempdir_in(&parent)
+    .map_err(|e| SilkSpoolError::io("create staging directory", &parent, e))
+}
+
+/// Decode `%XX` escapes in a URL path segment
+fn percent_decode(segment: &str) -> Option<String> {
+  let bytes = segment.as_bytes();
+  let mut decoded = Vec::with_capacity(bytes.len());
+  let mut i = 0;
+
+  while i < bytes.len() {
+    if bytes[i] == b'%' {
+      let hex = segment.get(i + 1..i + 3)?;
+      decoded.push(u8::from_str_radix(hex, 16).ok()?);
+      i += 3;
+    } else {
+      decoded.push(bytes[i]);
+      i += 1;
+    }
+  }
+
+  String::from_utf8(decoded).ok()
+}
+
+/// Name a single-file download after the last segment of its URL
+fn download_file_name(url: &str) -> String {
+  reqwest::Url::parse(url)
+    .ok()
+    .and_then(|parsed| parsed.path_segments()?.next_back().and_then(percent_decode))
+    .filter(|name| {
+      !name.is_empty()
+        && !name.starts_with('.')
+        && !name
+          .chars()
+          .any(|c| c.is_control() || matches!(c, '/' | '\\' | ':'))
+    })
+    .unwrap_or_else(|| format!("mod.{}", download_extension(url)))
+}
+
+/// Download a mod and unpack it into a staging directory
+async fn download_and_stage(
+  source: &DownloadSource,
+  game_path: &Path,
+  mod_name: &str,
+) -> Result<StagedMod> {
+  // The name becomes a folder under plugins that is cleared on install
+  validate_mod_folder_name(mod_name)?;
+
+  // Download the file, or reuse a previous download of the same URL
+  let temp_file = fetch_cached_download(source).await?;
+
+  // Detect the actual file type after download
+  let file_type = detect_file_type(&temp_file)?;
+
+  // Find BepInEx plugins directory
+  let plugins_dir = find_bepinex_plugins_dir(game_path)?;
+
+  let staging = create_staging_dir()?;
+  let staging_dir = staging.path();
+
+  // Unpack the file based on its detected type
+  let staged_files = match file_type {
+    FileType::Zip => extract_zip(&temp_file, staging_dir)?,
+    FileType::TarGz => extract_tar_gz(&temp_file, staging_dir)?,
+    FileType::Directory => copy_directory(&temp_file, staging_dir)?,
+    FileType::Dll | FileType::Other => {
+      copy_single_file(&temp_file, staging_dir, &download_file_name(&source.url))?
+    }
+  };
+
+  // DLL plugins go directly into the plugins directory, everything else into its own folder
+  let dest_root = if file_type == FileType::Dll {
+    plugins_dir
+  } else {
+    plugins_dir.join(mod_name)
+  };
+
+  let files = staged_files
+    .into_iter()
+    .map(|staged| {
+      let staged = PathBuf::from(staged);
+      let destination = match staged.strip_prefix(staging_dir) {
+        Ok(relative) => dest_root.join(relative),
+        Err(_) => dest_root.join(staged.file_name().unwrap_or_default()),
+      };
+      (staged, destination)
+    })
+    .collect();
+
+  Ok(StagedMod {
+    file_type,
+    files,
+    _staging_dir: staging,
+  })
+}
+
+/// Copy a staged mod into the game directory, applying the conflict policy
+fn apply_staged_mod(
+  staged: &StagedMod,
+  game_path: &Path,
+  mod_name: &str,
+  mod_id: &str,
+  policy: ConflictPolicy,
+) -> Result<InstallResult> {
+  let installed_data = load_installed_mods()?;
+  let index = FileOwnershipIndex::build(&installed_data.mods);
+
+  let destinations: Vec<String> = staged
+    .files
+    .iter()
+    .map(|(_, destination)| destination.to_string_lossy().to_string())
+    .collect();
+  let conflicts = index.find_conflicts(&destinations, mod_id);
+
+  if !conflicts.is_empty() && policy == ConflictPolicy::Abort {
+    return Ok(InstallResult {
+      success: false,
+      message: format!(
+        "{} file(s) would overwrite files owned by other mods",
+        conflicts.len()
+      ),
+      installed_files: vec![],
+      mod_folder_name: Some(mod_name.to_string()),
+      conflicts,
+    });
+  }
+
+  // Start from a clean mod directory unless another mod owns files inside it
+  let plugins_dir = find_bepinex_plugins_dir(game_path)?;
+  let mod_dir = plugins_dir.join(mod_name);
+  if mod_dir.exists() && !index.has_foreign_files_under(&mod_dir, mod_id) {
+    fs::remove_dir_all(&mod_dir)
+      .map_err(|e| SilkSpoolError::io("remove existing mod directory", &mod_dir, e))?;
+  }
+
+  let conflicting: HashSet<&str> = conflicts.iter().map(|c| c.path.as_str()).collect();
+  let mut installed_files = Vec::new();
+
+  for ((staged_file, destination), destination_str) in staged.files.iter().zip(&destinations) {
+    let destination =
+      if policy == ConflictPolicy::KeepBoth && conflicting.contains(destination_str.as_str()) {
+        keep_both_path(destination, mod_id)
+      } else {
+        destination.clone()
+      };
+
+    if let Some(parent) = destination.parent() {
+      fs::create_dir_all(parent).map_err(|e| SilkSpoolError::io("create directory", parent, e))?;
+    }
+
+    fs::copy(staged_file, &destination)
+      .map_err(|e| SilkSpoolError::io("copy file to", &destination, e))?;
+    installed_files.push(destination.to_string_lossy().to_string());
+  }
+
+  if policy == ConflictPolicy::Overwrite && !conflicts.is_empty() {
+    let overwritten: Vec<String> = conflicts.iter().map(|c| c.path.clone()).collect();
+    transfer_file_ownership(&overwritten, mod_id)?;
+  }
+
+  let message = match staged.file_type {
+    FileType::Zip | FileType::TarGz => format!(
+      "Successfully extracted and installed {} files",
+      installed_files.len()
+    ),
+    FileType::Dll => "Successfully installed DLL plugin".to_string(),
+    FileType::Directory => format!(
+      "Successfully copied directory with {} files",
+      installed_files.len()
+    ),
+    FileType::Other => format!("Successfully installed {} files", installed_files.len()),
+  };
+
+  Ok(InstallResult {
+    success: true,
+    message,
+    installed_files,
+    mod_folder_name: Some(mod_name.to_string()),
+    conflicts,
+  })
+}
+
+/// Install a mod from a downloaded file
+#[tracing::instrument(skip(source, game_path, mod_name, policy), err)]
+pub async fn install_mod(
+  source: &DownloadSource,
+  game_path: &Path,
+  mod_name: &str,
+  mod_id: &str,
+  policy: ConflictPolicy,
+) -> Result<InstallResult> {
+  let staged = download_and_stage(source, game_path, mod_name).await?;
+  let result = apply_staged_mod(&staged, game_path, mod_name, mod_id, policy);
+
+  if let Ok(install) = &result {
+    if install.success {
+      tracing::info!(files = install.installed_files.len(), "Installed mod");
+    } else {
+      tracing::warn!(conflicts = install.conflicts.len(), "{}", install.message);
+    }
+  }
+  result
+}
+
+/// List the files a mod would clash on, without installing it
+pub async fn check_install_conflicts(
+  source: &DownloadSource,
+  game_path: &Path,
+  mod_name: &str,
+  mod_id: &str,
+) -> Result<Vec<FileConflict>> {
+  let staged = download_and_stage(source, game_path, mod_name).await?;
+
+  let destinations: Vec<String> = staged
+    .files
+    .iter()
+    .map(|(_, destination)| destination.to_string_lossy().to_string())
+    .collect();
+
+  let installed_data = load_installed_mods()?;
+  Ok(FileOwnershipIndex::build(&installed_data.mods).find_conflicts(&destinations, mod_id))
+}
+
+/// Replace any installed copy of a mod with a specific version, then apply its enabled flag
+///
+/// The new version is downloaded and unpacked before the installed one is touched, and the
+/// installed version is put back if the new one cannot be installed. The outgoing version is
+/// archived so it can be rolled back to.
+#[tracing::instrument(skip(mod_title, source, game_path, policy), err)]
+pub async fn install_tracked_version(
+  mod_id: &str,
+  mod_title: &str,
+  version: &str,
+  source: &DownloadSource,
+  game_path: &Path,
+  enabled: bool,
+  policy: ConflictPolicy,
+) -> Result<()> {
+  validate_mod_id(mod_id)?;
+
+  let staged = download_and_stage(source, game_path, mod_title).await?;
+  replace_tracked_version(
+    &staged,
+    mod_id,
+    mod_title,
+    version,
+    &source.url,
+    game_path,
+    policy,
+  )?;
+
+  if !enabled {
+    disable_mod(mod_id, game_path)?;
+  }
+
+  Ok(())
+}
+
+/// Swap the installed version of a mod for a staged one, archiving the outgoing version
+fn replace_tracked_version(
+  staged: &StagedMod,
+  mod_id: &str,
+  mod_title: &str,
+  version: &str,
+  download_url: &str,
+  game_path: &Path,
+  policy: ConflictPolicy,
+) -> Result<()> {
+  // Keep a copy of the outgoing version so it can be rolled back to
+  let previous = match get_installed_mod(mod_id, game_path)? {
+    Some(previous) => {
+      let record = archive_version(&previous)?;
+      uninstall_tracked_mod(mod_id, game_path)?;
+      Some((previous, record))
+    }
+    None => None,
+  };
+
+  let installed =
+    apply_staged_mod(staged, game_path, mod_title, mod_id, policy).and_then(|result| {
+      if !result.success {
+        return Err(SilkSpoolError::FileConflict {
+          mod_id: mod_id.to_string(),
+          message: result.message,
+        });
+      }
+
+      add_installed_mod(
+        mod_id.to_string(),
+        mod_title.to_string(),
+        version.to_string(),
+        result.installed_files,
+        game_path.to_string_lossy().to_string(),
+        Some(download_url.to_string()),
+      )
+    });
+
+  match (installed, previous) {
+    (Ok(()), Some((previous, record))) => {
+      let mut history = previous.history;
+      history.push(record);
+      set_version_history(mod_id, game_path, history)?;
+      if previous.pinned_version.is_some() {
+        set_mod_pinned(mod_id, game_path, true)?;
+      }
+      Ok(())
+    }
+    (Ok(()), None) => Ok(()),
+    (Err(error), previous) => {
+      if let Err(e) = restore_replaced_version(staged, mod_id, previous) {
+        tracing::error!(error = %e, "Failed to restore the replaced version");
+      }
+      Err(error)
+    }
+  }
+}
+
+/// Remove what a failed install copied, then put back the version it was replacing
+fn restore_replaced_version(
+  staged: &StagedMod,
+  mod_id: &str,
+  previous: Option<(InstalledMod, ModVersionRecord)>,
+) -> Result<()> {
+  let game_path = previous
+    .as_ref()
+    .map(|(previous, _)| PathBuf::from(&previous.game_path));
+  let index = FileOwnershipIndex::build(&load_installed_mods()?.mods);
+
+  for (_, destination) in &staged.files {
+    let destination_str = destination.to_string_lossy();
+    if destination.exists() && index.owners_of(&destination_str).is_empty() {
+      fs::remove_file(destination)
+        .map_err(|e| SilkSpoolError::io("remove file", destination, e))?;
+      if let Some(game_path) = &game_path {
+        remove_empty_parents(destination, game_path);
+      }
+    }
+  }
+
+  if let Some((previous, record)) = previous {
+    if let Some(archive) = record.archive_path.as_deref().map(Path::new) {
+      restore_archived_files(&previous, archive)?;
+      let _ = fs::remove_dir_all(archive);
+    }
+    restore_installed_mod(previous)?;
+  }
+
+  tracing::warn!(
+    mod_id,
+    "Restored the previous version after a failed install"
+  );
+  Ok(())
+}
+
+/// Check that a mod ID is safe to name the holding and archive folders kept for the mod
+///
+/// The folder under `plugins` is named after the title instead, see `validate_mod_folder_name`.
+pub fn validate_mod_id(mod_id: &str) -> Result<()> {
+  let valid = !mod_id.is_empty()
+    && mod_id.len() <= 128
+    && !mod_id.starts_with('.')
+    && mod_id
+      .chars()
+      .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'));
+
+  if valid {
+    Ok(())
+  } else {
+    Err(SilkSpoolError::invalid_input(format!(
+      "Invalid mod ID '{}': only letters, digits, '-', '_' and '.' are allowed",
+      mod_id
+    )))
+  }
+}
+
+/// Check that a mod title can name its folder inside the plugins directory
+pub fn validate_mod_folder_name(mod_name: &str) -> Result<()> {
+  let mut components = Path::new(mod_name).components();
+  let single_folder = matches!(
+    (components.next(), components.next()),
+    (Some(std::path::Component::Normal(_)), None)
+  );
+  let valid = single_folder
+    && mod_name.trim() == mod_name
+    && !mod_name.starts_with('.')
+    && !mod_name
+      .chars()
+      .any(|c| c.is_control() || matches!(c, '/' | '\\' | ':'));
+
+  if valid {
+    Ok(())
+  } else {
+    Err(SilkSpoolError::invalid_input(format!(
+      "Invalid mod title '{}': it must be a single folder name",
+      mod_name
+    )))
+  }
+}
+
+/// Get the directory where replaced mod versions are archived
+fn get_version_archive_dir() -> Result<PathBuf> {
+  Ok(get_app_cache_dir()?.join("archive"))
+}
+
+/// Copy the files of an installed version into the archive and describe it as a history entry
+fn archive_version(installed_mod: &InstalledMod) -> Result<ModVersionRecord> {
+  validate_mod_id(&installed_mod.mod_id)?;
+  // The same mod can be installed into several games, so each game gets its own archive
+  let game_dir = format!("{:x}", Sha256::digest(installed_mod.game_path.as_bytes()));
+  let version_dir: String = installed_mod
+    .version
+    .chars()
+    .map(|c| {
+      if c.is_ascii_alphanumeric() || "._-+".contains(c) {
+        c
+      } else {
+        '_'
+      }
+    })
+    .collect();
+  let archive_dir = get_version_archive_dir()?
+    .join(&game_dir[..16])
+    .join(&installed_mod.mod_id)
+    .join(version_dir);
+
+  let _ = fs::remove_dir_all(&archive_dir);
+  archive_tracked_files(installed_mod, &archive_dir)?;
+
+  Ok(ModVersionRecord {
+    version: installed_mod.version.clone(),
+    installed_files: installed_mod.installed_files.clone(),
+    download_url: installed_mod.download_url.clone(),
+    replaced_at: chrono::Utc::now().to_rfc3339(),
+    archive_path: Some(archive_dir.to_string_lossy().to_string()),
+  })
+}
+
+/// Put back the version a mod had before its last update
+///
+/// Archived files are restored exactly; versions without an archive are reinstalled from their
+/// recorded download URL. `local_root` is the directory of the local repository listing the mod,
+/// if any.
+#[tracing::instrument(skip(game_path, local_root), err)]
+pub async fn rollback_mod(
+  mod_id: &str,
+  game_path: &Path,
+  local_root: Option<&Path>,
+) -> Result<InstallResult> {
+  validate_mod_id(mod_id)?;
+  let current =
+    get_installed_mod(mod_id, game_path)?.ok_or_else(|| SilkSpoolError::mod_not_found(mod_id))?;
+  let mut history = current.history.clone();
+  let target = history
+    .pop()
+    .ok_or_else(|| SilkSpoolError::DownloadUnavailable {
+      mod_id: mod_id.to_string(),
+      message: format!("No previous version recorded for {}", mod_id),
+    })?;
+
+  let archive = target
+    .archive_path
+    .as_ref()
+    .map(PathBuf::from)
+    .filter(|path| path.exists());
+
+  if let Some(archive) = archive {
+    let restored = InstalledMod {
+      version: target.version.clone(),
+      installed_files: target.installed_files.clone(),
+      download_url: target.download_url.clone(),
+      installed_at: chrono::Utc::now().to_rfc3339(),
+      pinned_version: current
+        .pinned_version
+        .as_ref()
+        .map(|_| target.version.clone()),
+      history,
+      ..current.clone()
+    };
+
+    uninstall_tracked_mod(mod_id, game_path)?;
+    restore_archived_files(&restored, &archive)?;
+    restore_installed_mod(restored)?;
+    let _ = fs::remove_dir_all(&archive);
+  } else {
+    let download_url =
+      target
+        .download_url
+        .clone()
+        .ok_or_else(|| SilkSpoolError::DownloadUnavailable {
+          mod_id: mod_id.to_string(),
+          message: format!(
+            "No archive or download URL recorded for {} {}",
+            mod_id, target.version
+          ),
+        })?;
+
+    let source = DownloadSource {
+      url: download_url,
+      local_root: local_root.map(Path::to_path_buf),
+    };
+
+    // The current version is archived while it is replaced, and put back if that fails
+    install_tracked_version(
+      mod_id,
+      &current.mod_title,
+      &target.version,
+      &source,
+      game_path,
+      current.enabled,
+      ConflictPolicy::Abort,
+    )
+    .await?;
+
+    // Rolling back discards the current version, so its new archive is not kept either
+    let replaced = get_installed_mod(mod_id, game_path)?.and_then(|m| m.history.last().cloned());
+    if let Some(archive) = replaced.and_then(|record| record.archive_path) {
+      let _ = fs::remove_dir_all(archive);
+    }
+    set_version_history(mod_id, game_path, history)?;
+  }
+
+  tracing::info!(version = %target.version, "Rolled back mod");
+  Ok(InstallResult {
+    success: true,
+    message: format!(
+      "Rolled back {} to version {}",
+      current.mod_title, target.version
+    ),
+    installed_files: target.installed_files,
+    mod_folder_name: Some(current.mod_title),
+    conflicts: vec![],
+  })
+}
+
+/// Uninstall a tracked mod from a game by removing exactly the files it installed
+#[tracing::instrument(skip(game_path), err)]
+pub fn uninstall_tracked_mod(mod_id: &str, game_path: &Path) -> Result<InstallResult> {
+  let installed_mod =
+    get_installed_mod(mod_id, game_path)?.ok_or_else(|| SilkSpoolError::mod_not_found(mod_id))?;
+  let holding_dir = disabled_mod_dir(game_path, mod_id);
+
+  for file in &installed_mod.installed_files {
+    let path = tracked_file_location(&installed_mod, file);
+
+    if path.exists() {
+      fs::remove_file(&path).map_err(|e| SilkSpoolError::io("remove file", &path, e))?;
+      remove_empty_parents(&path, game_path);
+    }
+  }
+
+  let _ = fs::remove_dir_all(&holding_dir);
+  remove_installed_mod(mod_id, game_path)?;
+  tracing::info!(
+    files = installed_mod.installed_files.len(),
+    "Uninstalled mod"
+  );
+
+  Ok(InstallResult {
+    success: true,
+    message: "Mod uninstalled successfully".to_string(),
+    installed_files: vec![],
+    mod_folder_name: Some(installed_mod.mod_title),
+    conflicts: vec![],
+  })
+}
+
+/// Uninstall a mod by removing its directory or files
+#[tracing::instrument(skip(game_path), err)]
+pub fn uninstall_mod(game_path: &Path, mod_name: &str) -> Result<InstallResult> {
+  let plugins_dir = find_bepinex_plugins_dir(game_path)?;
+  let mod_dir = plugins_dir.join(mod_name);
+
+  // Check if it's a directory-based mod
+  if mod_dir.exists() {
+    fs::remove_dir_all(&mod_dir)
+      .map_err(|e| SilkSpoolError::io("remove mod directory", &mod_dir, e))?;
+
+    return Ok(InstallResult {
+      success: true,
+      message: "Mod uninstalled successfully".to_string(),
+      installed_files: vec![],
+      mod_folder_name: Some(mod_name.to_string()),
+      conflicts: vec![],
+    });
+  }
+
+  // Check if it's a single DLL file (look for DLL files that might match the mod name)
+  let dll_file = plugins_dir.join(format!("{}.dll", mod_name));
+  if dll_file.exists() {
+    fs::remove_file(&dll_file).map_err(|e| SilkSpoolError::io("remove DLL file", &dll_file, e))?;
+
+    return Ok(InstallResult {
+      success: true,
+      message: "DLL mod uninstalled successfully".to_string(),
+      installed_files: vec![],
+      mod_folder_name: Some(mod_name.to_string()),
+      conflicts: vec![],
+    });
+  }
+
+  // Mod not found
+  Ok(InstallResult {
+    success: false,
+    message: "Mod not found".to_string(),
+    installed_files: vec![],
+    mod_folder_name: Some(mod_name.to_string()),
+    conflicts: vec![],
+  })
+}
+
+/// List installed mods, including the ones that are currently disabled
+pub fn list_installed_mods(game_path: &Path) -> Result<Vec<InstalledModEntry>> {
+  let plugins_dir = find_bepinex_plugins_dir(game_path)?;
+
+  let mut mods = Vec::new();
+
+  if let Ok(entries) = fs::read_dir(&plugins_dir) {
+    for entry in entries.flatten() {
+      if let Some(name) = entry.file_name().to_str() {
+        if entry.file_type().map(|ft| ft.is_dir()).unwrap_or(false) {
+          // Directory-based mod
+          mods.push(InstalledModEntry {
+            name: name.to_string(),
+            enabled: true,
+          });
+        } else if name.ends_with(".dll") {
+          // Single DLL file mod (remove .dll extension for consistency)
+          let mod_name = name.trim_end_matches(".dll");
+          mods.push(InstalledModEntry {
+            name: mod_name.to_string(),
+            enabled: true,
+          });
+        }
+      }
+    }
+  }
+
+  // Disabled mods only live in the holding area, so they come from the tracking data
+  let disabled_mods = get_all_installed_mods()?
+    .into_iter()
+    .filter(|mod_| !mod_.enabled && Path::new(&mod_.game_path) == game_path);
+
+  for disabled_mod in disabled_mods {
+    mods.push(InstalledModEntry {
+      name: disabled_mod.mod_title,
+      enabled: false,
+    });
+  }
+
+  Ok(mods)
+}
+
+/// Get the holding area for a disabled mod's files
+fn disabled_mod_dir(game_path: &Path, mod_id: &str) -> PathBuf {
+  game_path.join("silk-spool").join("disabled").join(mod_id)
+}
+
+/// Get where a tracked file currently lives, which is the holding area for disabled mods
+pub fn tracked_file_location(installed_mod: &InstalledMod, file: &str) -> PathBuf {
+  let game_path = Path::new(&installed_mod.game_path);
+  let path = PathBuf::from(file);
+
+  match (installed_mod.enabled, path.strip_prefix(game_path)) {
+    (false, Ok(relative)) => disabled_mod_dir(game_path, &installed_mod.mod_id).join(relative),
+    _ => path,
+  }
+}
+
+/// Copy the files of a tracked mod into `archive_dir`, keyed by their path inside the game
+pub fn archive_tracked_files(installed_mod: &InstalledMod, archive_dir: &Path) -> Result<()> {
+  let game_path = Path::new(&installed_mod.game_path);
+
+  for file in &installed_mod.installed_files {
+    let relative = Path::new(file)
+      .strip_prefix(game_path)
+      .map_err(|_| outside_game_directory(file))?;
+    let source = tracked_file_location(installed_mod, file);
+
+    if !source.exists() {
+      continue;
+    }
+
+    let destination = archive_dir.join(relative);
+    if let Some(parent) = destination.parent() {
+      fs::create_dir_all(parent)
+        .map_err(|e| SilkSpoolError::io("create archive directory", parent, e))?;
+    }
+    fs::copy(&source, &destination).map_err(|e| SilkSpoolError::io("archive", &source, e))?;
+  }
+
+  Ok(())
+}
+
+/// Put the files archived by `archive_tracked_files` back where the mod expects them
+pub fn restore_archived_files(installed_mod: &InstalledMod, archive_dir: &Path) -> Result<()> {
+  let game_path = Path::new(&installed_mod.game_path);
+
+  for file in &installed_mod.installed_files {
+    let Ok(relative) = Path::new(file).strip_prefix(game_path) else {
+      continue;
+    };
+    let source = archive_dir.join(relative);
+
+    if !source.exists() {
+      continue;
+    }
+
+    let destination = tracked_file_location(installed_mod, file);
+    if let Some(parent) = destination.parent() {
+      fs::create_dir_all(parent).map_err(|e| SilkSpoolError::io("create directory", parent, e))?;
+    }
+    fs::copy(&source, &destination).map_err(|e| SilkSpoolError::io("restore", &destination, e))?;
+  }
+
+  Ok(())
+}
+
+/// Describe a tracked file that does not live under the game directory it was installed to
+fn outside_game_directory(file: &str) -> SilkSpoolError {
+  SilkSpoolError::invalid_input(format!("File {} is outside the game directory", file))
+}
+
+/// Move a file, falling back to copy and delete across file systems
+fn move_file(from: &Path, to: &Path) -> Result<()> {
+  if let Some(parent) = to.parent() {
+    fs::create_dir_all(parent).map_err(|e| SilkSpoolError::io("create directory", parent, e))?;
+  }
+
+  if fs::rename(from, to).is_err() {
+    fs::copy(from, to).map_err(|e| SilkSpoolError::io("move file", from, e))?;
+    fs::remove_file(from).map_err(|e| SilkSpoolError::io("remove file", from, e))?;
+  }
+
+  Ok(())
+}
+
+/// Remove directories left empty after moving files, stopping at `root`
+fn remove_empty_parents(file: &Path, root: &Path) {
+  let mut dir = file.parent();
+
+  while let Some(current) = dir {
+    if current == root || !current.starts_with(root) || fs::remove_dir(current).is_err() {
+      break;
+    }
+    dir = current.parent();
+  }
+}
+
+/// Move every tracked file of a mod between the game and its holding area
+fn move_mod_files(installed_mod: &InstalledMod, to_holding_area: bool) -> Result<usize> {
+  let game_path = Path::new(&installed_mod.game_path);
+  let holding_dir = disabled_mod_dir(game_path, &installed_mod.mod_id);
+  let mut moved: Vec<(PathBuf, PathBuf)> = Vec::new();
+
+  for file in &installed_mod.installed_files {
+    let active_path = PathBuf::from(file);
+    let relative = active_path
+      .strip_prefix(game_path)
+      .map_err(|_| outside_game_directory(file))?;
+    let held_path = holding_dir.join(relative);
+
+    let (from, to) = if to_holding_area {
+      (active_path.clone(), held_path)
+    } else {
+      (held_path, active_path.clone())
+    };
+
+    // Tracked files that have gone missing are left for the user to reinstall
+    if !from.exists() {
+      continue;
+    }
+
+    if let Err(e) = move_file(&from, &to) {
+      // Put back whatever was already moved so the mod is not left half-disabled
+      for (moved_from, moved_to) in moved.iter().rev() {
+        let _ = move_file(moved_to, moved_from);
+      }
+      return Err(e);
+    }
+
+    moved.push((from, to));
+  }
+
+  for (from, _) in &moved {
+    let root = if to_holding_area {
+      game_path.to_path_buf()
+    } else {
+      holding_dir.clone()
+    };
+    remove_empty_parents(from, &root);
+  }
+
+  if !to_holding_area {
+    let _ = fs::remove_dir(&holding_dir);
+  }
+
+  Ok(moved.len())
+}
+
+/// Disable a mod by moving its files into the holding area
+#[tracing::instrument(skip(game_path), err)]
+pub fn disable_mod(mod_id: &str, game_path: &Path) -> Result<InstallResult> {
+  let installed_mod =
+    get_installed_mod(mod_id, game_path)?.ok_or_else(|| SilkSpoolError::mod_not_found(mod_id))?;
+
+  if !installed_mod.enabled {
+    return Ok(InstallResult {
+      success: true,
+      message: "Mod is already disabled".to_string(),
+      installed_files: vec![],
+      mod_folder_name: Some(installed_mod.mod_title),
+      conflicts: vec![],
+    });
+  }
+
+  let moved = move_mod_files(&installed_mod, true)?;
+  set_mod_enabled(mod_id, game_path, false)?;
+  tracing::info!(files = moved, "Disabled mod");
+
+  Ok(InstallResult {
+    success: true,
+    message: format!("Mod disabled ({} files moved)", moved),
+    installed_files: vec![],
+    mod_folder_name: Some(installed_mod.mod_title),
+    conflicts: vec![],
+  })
+}
+
+/// Enable a previously disabled mod by restoring its files
+#[tracing::instrument(skip(game_path), err)]
+pub fn enable_mod(mod_id: &str, game_path: &Path) -> Result<InstallResult> {
+  let installed_mod =
+    get_installed_mod(mod_id, game_path)?.ok_or_else(|| SilkSpoolError::mod_not_found(mod_id))?;
+
+  if installed_mod.enabled {
+    return Ok(InstallResult {
+      success: true,
+      message: "Mod is already enabled".to_string(),
+      installed_files: installed_mod.installed_files,
+      mod_folder_name: Some(installed_mod.mod_title),
+      conflicts: vec![],
+    });
+  }
+
+  // Files installed by other mods while this one was disabled must not be clobbered
+  let index = FileOwnershipIndex::build(&load_installed_mods()?.mods);
+  let conflicts = index.find_conflicts(&installed_mod.installed_files, mod_id);
+  if !conflicts.is_empty() {
+    return Ok(InstallResult {
+      success: false,
+      message: format!(
+        "{} file(s) are now owned by other mods; remove them before enabling this one",
+        conflicts.len()
+      ),
+      installed_files: vec![],
+      mod_folder_name: Some(installed_mod.mod_title),
+      conflicts,
+    });
+  }
+
+  let moved = move_mod_files(&installed_mod, false)?;
+  set_mod_enabled(mod_id, game_path, true)?;
+  tracing::info!(files = moved, "Enabled mod");
+
+  Ok(InstallResult {
+    success: true,
+    message: format!("Mod enabled ({} files restored)", moved),
+    installed_files: installed_mod.installed_files,
+    mod_folder_name: Some(installed_mod.mod_title),
+    conflicts: vec![],
+  })
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::config::test_dirs;
+
+  fn file_url(path: &Path) -> String {
+    reqwest::Url::from_file_path(path).unwrap().to_string()
+  }
+
+  /// A download listed by a local repository in the file's directory
+  fn local_source(path: &Path) -> DownloadSource {
+    DownloadSource {
+      url: file_url(path),
+      local_root: path.parent().map(Path::to_path_buf),
+    }
+  }
+
+  #[test]
+  fn test_download_sources_only_read_inside_local_repositories() {
+    let dirs = tempfile::tempdir().unwrap();
+    let repo = dirs.path().join("repo");
+    fs::create_dir_all(&repo).unwrap();
+    fs::write(repo.join("a.dll"), b"a").unwrap();
+    fs::write(dirs.path().join("secret.txt"), b"secret").unwrap();
+    let source = |path: &Path| DownloadSource {
+      url: file_url(path),
+      local_root: Some(repo.clone()),
+    };
+
+    assert_eq!(
+      source(&repo.join("a.dll")).local_path().unwrap(),
+      Some(repo.join("a.dll").canonicalize().unwrap())
+    );
+    assert!(source(&dirs.path().join("secret.txt"))
+      .local_path()
+      .is_err());
+    assert!(source(&repo.join("../secret.txt")).local_path().is_err());
+    assert!(DownloadSource::remote(&file_url(&repo.join("a.dll")))
+      .local_path()
+      .is_err());
+    assert_eq!(
+      DownloadSource::remote("https://example.com/a.dll")
+        .local_path()
+        .unwrap(),
+      None
+    );
+  }
+
+  #[test]
+  fn test_move_mod_files_round_trip() {
+    let game_dir = tempfile::tempdir().unwrap();
+    let plugin = game_dir
+      .path()
+      .join("BepInEx")
+      .join("plugins")
+      .join("Test Mod")
+      .join("TestMod.dll");
+    fs::create_dir_all(plugin.parent().unwrap()).unwrap();
+    fs::write(&plugin, b"dll").unwrap();
+
+    let installed_mod = InstalledMod {
+      mod_id: "test-mod".to_string(),
+      mod_title: "Test Mod".to_string(),
+      version: "1.0.0".to_string(),
+      installed_at: "2024-01-01T00:00:00Z".to_string(),
+      installed_files: vec![plugin.to_string_lossy().to_string()],
+      game_path: game_dir.path().to_string_lossy().to_string(),
+      download_url: None,
+      enabled: true,
+      pinned_version: None,
+      history: vec![],
+    };
+
+    assert_eq!(move_mod_files(&installed_mod, true).unwrap(), 1);
+    assert!(!plugin.exists());
+    assert!(!plugin.parent().unwrap().exists());
+    assert!(disabled_mod_dir(game_dir.path(), "test-mod")
+      .join("BepInEx/plugins/Test Mod/TestMod.dll")
+      .exists());
+
+    assert_eq!(move_mod_files(&installed_mod, false).unwrap(), 1);
+    assert!(plugin.exists());
+    assert!(!disabled_mod_dir(game_dir.path(), "test-mod").exists());
+  }
+
+  #[test]
+  fn test_archive_and_restore_tracked_files() {
+    let game_dir = tempfile::tempdir().unwrap();
+    let archive_dir = tempfile::tempdir().unwrap();
+    let plugin = game_dir.path().join("BepInEx/plugins/Test Mod/TestMod.dll");
+    fs::create_dir_all(plugin.parent().unwrap()).unwrap();
+    fs::write(&plugin, b"v1").unwrap();
+
+    let installed_mod = InstalledMod {
+      mod_id: "test-mod".to_string(),
+      mod_title: "Test Mod".to_string(),
+      version: "1.0.0".to_string(),
+      installed_at: "2024-01-01T00:00:00Z".to_string(),
+      installed_files: vec![plugin.to_string_lossy().to_string()],
+      game_path: game_dir.path().to_string_lossy().to_string(),
+      download_url: None,
+      enabled: true,
+      pinned_version: None,
+      history: vec![],
+    };
+
+    archive_tracked_files(&installed_mod, archive_dir.path()).unwrap();
+    fs::remove_dir_all(game_dir.path().join("BepInEx")).unwrap();
+
+    restore_archived_files(&installed_mod, archive_dir.path()).unwrap();
+    assert_eq!(fs::read(&plugin).unwrap(), b"v1");
+  }
+
+  #[tokio::test]
+  async fn test_failed_replacement_keeps_the_installed_version() {
+    let dirs = test_dirs::isolate();
+    let game_dir = tempfile::tempdir().unwrap();
+    fs::create_dir_all(game_dir.path().join("BepInEx/plugins")).unwrap();
+    let v1 = dirs.path().join("test-mod-1.0.0.txt");
+    let v2 = dirs.path().join("test-mod-2.0.0.dll");
+    fs::write(&v1, b"v1").unwrap();
+    fs::write(&v2, b"v2").unwrap();
+
+    install_tracked_version(
+      "test-mod",
+      "Test Mod",
+      "1.0.0",
+      &local_source(&v1),
+      game_dir.path(),
+      true,
+      ConflictPolicy::Abort,
+    )
+    .await
+    .unwrap();
+    let installed = get_installed_mod("test-mod", game_dir.path())
+      .unwrap()
+      .unwrap();
+
+    // A download that cannot be fetched fails before anything is removed
+    let missing = local_source(&dirs.path().join("missing.dll"));
+    assert!(install_tracked_version(
+      "test-mod",
+      "Test Mod",
+      "2.0.0",
+      &missing,
+      game_dir.path(),
+      true,
+      ConflictPolicy::Abort
+    )
+    .await
+    .is_err());
+    assert_eq!(
+      get_installed_mod("test-mod", game_dir.path())
+        .unwrap()
+        .unwrap()
+        .installed_files,
+      installed.installed_files
+    );
+
+    // A conflict found while installing puts the old version back
+    let taken = game_dir.path().join("BepInEx/plugins/test-mod-2.0.0.dll");
+    add_installed_mod(
+      "other".to_string(),
+      "Other".to_string(),
+      "1.0.0".to_string(),
+      vec![taken.to_string_lossy().to_string()],
+      game_dir.path().to_string_lossy().to_string(),
+      None,
+    )
+    .unwrap();
+    let result = install_tracked_version(
+      "test-mod",
+      "Test Mod",
+      "2.0.0",
+      &local_source(&v2),
+      game_dir.path(),
+      true,
+      ConflictPolicy::Abort,
+    )
+    .await;
+    assert!(matches!(result, Err(SilkSpoolError::FileConflict { .. })));
+
+    let restored = get_installed_mod("test-mod", game_dir.path())
+      .unwrap()
+      .unwrap();
+    assert_eq!(restored.version, "1.0.0");
+    assert!(restored.history.is_empty());
+    assert_eq!(fs::read(&restored.installed_files[0]).unwrap(), b"v1");
+    assert!(!taken.exists());
+    let archived = walkdir::WalkDir::new(get_version_archive_dir().unwrap())
+      .into_iter()
+      .flatten()
+      .filter(|entry| entry.file_type().is_file())
+      .count();
+    assert_eq!(archived, 0);
+  }
+
+  #[tokio::test]
+  async fn test_single_file_mods_keep_their_file_names() {
+    let dirs = test_dirs::isolate();
+    let game_dir = tempfile::tempdir().unwrap();
+    fs::create_dir_all(game_dir.path().join("BepInEx/plugins")).unwrap();
+
+    for id in ["first", "second"] {
+      let plugin = dirs.path().join(format!("{}.dll", id));
+      fs::write(&plugin, id).unwrap();
+      install_tracked_version(
+        id,
+        id,
+        "1.0.0",
+        &local_source(&plugin),
+        game_dir.path(),
+        true,
+        ConflictPolicy::Abort,
+      )
+      .await
+      .unwrap();
+    }
+
+    let plugins = game_dir.path().join("BepInEx/plugins");
+    assert_eq!(fs::read(plugins.join("first.dll")).unwrap(), b"first");
+    assert_eq!(fs::read(plugins.join("second.dll")).unwrap(), b"second");
+  }
+
+  #[tokio::test]
+  async fn test_enable_mod_refuses_to_clobber_files_installed_while_disabled() {
+    let dirs = test_dirs::isolate();
+    let game_dir = tempfile::tempdir().unwrap();
+    fs::create_dir_all(game_dir.path().join("BepInEx/plugins")).unwrap();
+    fs::create_dir_all(game_dir.path().join("BepInEx/core")).unwrap();
+    fs::write(game_dir.path().join("BepInEx/core/BepInEx.dll"), b"core").unwrap();
+    let shared = game_dir.path().join("BepInEx/plugins/Shared.dll");
+
+    for id in ["a", "b"] {
+      let plugin = dirs.path().join(id).join("Shared.dll");
+      fs::create_dir_all(plugin.parent().unwrap()).unwrap();
+      fs::write(&plugin, id).unwrap();
+      install_tracked_version(
+        id,
+        id,
+        "1.0.0",
+        &local_source(&plugin),
+        game_dir.path(),
+        true,
+        ConflictPolicy::Abort,
+      )
+      .await
+      .unwrap();
+      if id == "a" {
+        disable_mod("a", game_dir.path()).unwrap();
+      }
+    }
+
+    let result = enable_mod("a", game_dir.path()).unwrap();
+    assert!(!result.success);
+    assert_eq!(result.conflicts[0].owners, vec!["b".to_string()]);
+    assert_eq!(fs::read(&shared).unwrap(), b"b");
+    assert!(
+      !get_installed_mod("a", game_dir.path())
+        .unwrap()
+        .unwrap()
+        .enabled
+    );
+    assert!(disabled_mod_dir(game_dir.path(), "a")
+      .join("BepInEx/plugins/Shared.dll")
+      .exists());
+  }
+
+  #[test]
+  fn test_download_file_name() {
+    assert_eq!(
+      download_file_name("https://example.com/files/My%20Mod.dll?download=1"),
+      "My Mod.dll"
+    );
+    assert_eq!(
+      download_file_name("https://example.com/get/..%2F..%2Fx.dll"),
+      "mod.dll"
+    );
+    assert_eq!(download_file_name("https://example.com/"), "mod.bin");
+  }
+
+  #[test]
+  fn test_validate_mod_folder_name() {
+    assert!(validate_mod_folder_name("Test Mod").is_ok());
+    assert!(validate_mod_folder_name("").is_err());
+    assert!(validate_mod_folder_name("..").is_err());
+    assert!(validate_mod_folder_name("../../..").is_err());
+    assert!(validate_mod_folder_name("/home/user").is_err());
+    assert!(validate_mod_folder_name("C:\\Windows").is_err());
+    assert!(validate_mod_folder_name("Mods/Test").is_err());
+  }
+
+  #[test]
+  fn test_validate_mod_id() {
+    assert!(validate_mod_id("author.mod-name_2").is_ok());
+    assert!(validate_mod_id("").is_err());
+    assert!(validate_mod_id("..").is_err());
+    assert!(validate_mod_id("../escape").is_err());
+    assert!(validate_mod_id("C:\\mods").is_err());
+  }
+}
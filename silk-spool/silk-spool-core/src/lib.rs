@@ -0,0 +1,22 @@
+pub mod catalog;
+pub mod config;
+pub mod conflicts;
+pub mod detect;
+pub mod doctor;
+pub mod error;
+pub mod installed_mods;
+pub mod installer;
+pub mod logging;
+pub mod modpack;
+pub mod profiles;
+pub mod repository;
+pub mod resolver;
+pub mod schema;
+pub mod steam;
+pub mod test_repo;
+pub mod types;
+pub mod updates;
+pub mod validation;
+
+#[cfg(test)]
+mod tests;
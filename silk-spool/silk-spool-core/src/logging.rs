@@ -0,0 +1,136 @@
+use std::{
+  fs,
+  path::{Path, PathBuf},
+  sync::atomic::{AtomicU64, Ordering},
+};
+
+use tracing::Span;
+use tracing_appender::{
+  non_blocking::WorkerGuard,
+  rolling::{RollingFileAppender, Rotation},
+};
+use tracing_subscriber::{fmt, prelude::*, EnvFilter};
+
+use crate::{
+  config::get_app_data_dir,
+  error::{Result, SilkSpoolError},
+};
+
+const LOG_FILE_PREFIX: &str = "silk-spool";
+const LOG_FILE_SUFFIX: &str = "log";
+
+/// Number of daily log files kept before the oldest is deleted
+const MAX_LOG_FILES: usize = 7;
+
+/// Get the directory the log files are written to
+pub fn get_log_dir() -> Result<PathBuf> {
+  Ok(get_app_data_dir()?.join("logs"))
+}
+
+/// Send log events to a daily rotating file in `log_dir`
+///
+/// Events are written on a background thread until the returned guard is dropped, so keep it
+/// alive for as long as the program runs. `RUST_LOG` overrides the default `info` level.
+pub fn init_logging(log_dir: &Path, echo_to_stderr: bool) -> Result<WorkerGuard> {
+  fs::create_dir_all(log_dir)
+    .map_err(|e| SilkSpoolError::io("create log directory", log_dir, e))?;
+  let appender = RollingFileAppender::builder()
+    .rotation(Rotation::DAILY)
+    .filename_prefix(LOG_FILE_PREFIX)
+    .filename_suffix(LOG_FILE_SUFFIX)
+    .max_log_files(MAX_LOG_FILES)
+    .build(log_dir)
+    .map_err(|e| SilkSpoolError::io("create log file in", log_dir, e))?;
+  let (writer, guard) = tracing_appender::non_blocking(appender);
+
+  let filter = EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info"));
+  tracing_subscriber::registry()
+    .with(filter)
+    .with(fmt::layer().with_writer(writer).with_ansi(false))
+    .with(echo_to_stderr.then(|| fmt::layer().with_writer(std::io::stderr)))
+    .try_init()
+    .map_err(|e| SilkSpoolError::other(format!("Failed to start logging: {}", e)))?;
+
+  Ok(guard)
+}
+
+/// Create an ID that ties together the log lines of one operation
+pub fn new_job_id() -> String {
+  static NEXT_JOB: AtomicU64 = AtomicU64::new(1);
+  format!(
+    "{}-{}",
+    std::process::id(),
+    NEXT_JOB.fetch_add(1, Ordering::Relaxed)
+  )
+}
+
+/// Create the span for an operation started by the user, e.g. `job_span("update_all")`
+pub fn job_span(kind: &str) -> Span {
+  tracing::info_span!("job", job_id = %new_job_id(), kind)
+}
+
+/// List the log files in `log_dir`, oldest first
+pub fn log_files(log_dir: &Path) -> Result<Vec<PathBuf>> {
+  if !log_dir.exists() {
+    return Ok(Vec::new());
+  }
+
+  let mut files: Vec<PathBuf> = fs::read_dir(log_dir)
+    .map_err(|e| SilkSpoolError::io("read log directory", log_dir, e))?
+    .flatten()
+    .map(|entry| entry.path())
+    .filter(|path| {
+      path.is_file()
+        && path
+          .file_name()
+          .and_then(|name| name.to_str())
+          .is_some_and(|name| name.starts_with(LOG_FILE_PREFIX))
+    })
+    .collect();
+  // File names end in the date, so name order is also age order
+  files.sort();
+  Ok(files)
+}
+
+/// Get the last `count` logged lines, oldest first, reading back through rotated files as needed
+pub fn recent_log_lines(log_dir: &Path, count: usize) -> Result<Vec<String>> {
+  let mut lines = Vec::new();
+
+  for file in log_files(log_dir)?.iter().rev() {
+    if lines.len() >= count {
+      break;
+    }
+
+    let contents =
+      fs::read_to_string(file).map_err(|e| SilkSpoolError::io("read log file", file, e))?;
+    let mut file_lines: Vec<String> = contents.lines().map(String::from).collect();
+    let skip = file_lines.len().saturating_sub(count - lines.len());
+    file_lines.drain(..skip);
+    file_lines.append(&mut lines);
+    lines = file_lines;
+  }
+
+  Ok(lines)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use tempfile::TempDir;
+
+  #[test]
+  fn test_recent_log_lines_reads_back_through_rotated_files() {
+    let temp_dir = TempDir::new().unwrap();
+    let log_dir = temp_dir.path();
+    fs::write(log_dir.join("silk-spool.2026-01-01.log"), "a\nb\nc\n").unwrap();
+    fs::write(log_dir.join("silk-spool.2026-01-02.log"), "d\ne\n").unwrap();
+    fs::write(log_dir.join("unrelated.txt"), "x\n").unwrap();
+
+    assert_eq!(recent_log_lines(log_dir, 1).unwrap(), vec!["e"]);
+    assert_eq!(recent_log_lines(log_dir, 3).unwrap(), vec!["c", "d", "e"]);
+    assert_eq!(recent_log_lines(log_dir, 10).unwrap().len(), 5);
+    assert!(recent_log_lines(&log_dir.join("missing"), 10)
+      .unwrap()
+      .is_empty());
+  }
+}
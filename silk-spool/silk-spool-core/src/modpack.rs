@@ -0,0 +1,454 @@
+use std::{
+  fs,
+  path::{Component, Path, PathBuf},
+};
+
+use serde::{Deserialize, Serialize};
+use walkdir::WalkDir;
+
+use crate::{
+  catalog::{load_catalog, Catalog},
+  config::{add_repo, load_config, RepoEntry},
+  conflicts::ConflictPolicy,
+  error::{Result, SilkSpoolError},
+  installer::{fetch_cached_download, file_sha256, install_tracked_version, DownloadSource},
+  profiles::{get_profile, installed_profile_mods, ProfileMod},
+  repository::RepositoryManager,
+  types::ModActionFailure,
+};
+
+/// Current version of the modpack file format
+pub const MODPACK_FORMAT_VERSION: u32 = 1;
+
+/// A portable description of a full mod setup
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Modpack {
+  pub format_version: u32,
+  pub name: String,
+  pub created_at: String,
+  /// URLs of the configured repositories the mods were found in
+  pub repositories: Vec<String>,
+  pub mods: Vec<ModpackMod>,
+  #[serde(default)]
+  pub config_files: Vec<ModpackConfigFile>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModpackMod {
+  pub mod_id: String,
+  pub title: String,
+  pub version: String,
+  pub enabled: bool,
+  pub repo_id: Option<String>,
+  pub download_url: String,
+  /// SHA-256 of the downloaded file
+  pub sha256: String,
+}
+
+/// A BepInEx config file bundled with a modpack
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModpackConfigFile {
+  /// Path relative to `BepInEx/config`, always with forward slashes
+  pub path: String,
+  pub contents: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ModpackImportResult {
+  /// True when repositories are missing and nothing was changed yet
+  pub needs_confirmation: bool,
+  pub missing_repositories: Vec<String>,
+  pub installed: Vec<String>,
+  pub failed: Vec<ModActionFailure>,
+  pub warnings: Vec<String>,
+  pub config_files_written: usize,
+}
+
+/// Build a modpack from a profile, or from the installed mods when no profile is given
+pub async fn export_modpack(
+  name: &str,
+  profile: Option<&str>,
+  game_path: &Path,
+  include_configs: bool,
+  repo_manager: &RepositoryManager,
+) -> Result<Modpack> {
+  let entries: Vec<ProfileMod> = match profile {
+    Some(profile_name) => get_profile(profile_name)?.mods,
+    None => installed_profile_mods(game_path)?,
+  };
+
+  let catalog = load_catalog(repo_manager).await?;
+  let mut mods = Vec::new();
+  let mut repositories: Vec<String> = Vec::new();
+
+  for entry in entries {
+    let download_url = entry
+      .download_url
+      .ok_or_else(|| SilkSpoolError::DownloadUnavailable {
+        message: format!("No download URL recorded for {}", entry.mod_id),
+        mod_id: entry.mod_id.clone(),
+      })?;
+
+    // Checksums come from the cached download, fetching it if needed
+    let cached_file =
+      fetch_cached_download(&catalog.download_source(&entry.mod_id, &download_url)).await?;
+
+    let source = catalog.find(&entry.mod_id);
+    if let Some(source) = source {
+      if !repositories.contains(&source.repo_url) {
+        repositories.push(source.repo_url.clone());
+      }
+    }
+
+    mods.push(ModpackMod {
+      repo_id: source.map(|m| m.repo_id.clone()),
+      sha256: file_sha256(&cached_file)?,
+      mod_id: entry.mod_id,
+      title: entry.title,
+      version: entry.version,
+      enabled: entry.enabled,
+      download_url,
+    });
+  }
+
+  let config_files = if include_configs {
+    collect_config_files(game_path)?
+  } else {
+    Vec::new()
+  };
+
+  Ok(Modpack {
+    format_version: MODPACK_FORMAT_VERSION,
+    name: name.to_string(),
+    created_at: chrono::Utc::now().to_rfc3339(),
+    repositories,
+    mods,
+    config_files,
+  })
+}
+
+/// Write a modpack to a file
+pub fn write_modpack(modpack: &Modpack, path: &Path) -> Result<()> {
+  let content = serde_json::to_string_pretty(modpack)
+    .map_err(|e| SilkSpoolError::other(format!("Failed to serialize modpack: {}", e)))?;
+
+  fs::write(path, content).map_err(|e| SilkSpoolError::io("write modpack file", path, e))
+}
+
+/// Read a modpack from a file
+pub fn read_modpack(path: &Path) -> Result<Modpack> {
+  let content =
+    fs::read_to_string(path).map_err(|e| SilkSpoolError::io("read modpack file", path, e))?;
+
+  let modpack: Modpack =
+    serde_json::from_str(&content).map_err(|e| SilkSpoolError::parse("modpack file", e))?;
+
+  if modpack.format_version > MODPACK_FORMAT_VERSION {
+    return Err(SilkSpoolError::invalid_input(format!(
+      "Modpack format version {} is newer than the supported version {}",
+      modpack.format_version, MODPACK_FORMAT_VERSION
+    )));
+  }
+
+  Ok(modpack)
+}
+
+/// Install everything in a modpack
+///
+/// Repositories the modpack needs but that are not configured are only added when
+/// `add_missing_repos` is set; otherwise nothing is changed and the missing ones are reported.
+#[tracing::instrument(skip_all, fields(modpack = %modpack.name), err)]
+pub async fn import_modpack(
+  modpack: &Modpack,
+  game_path: &Path,
+  add_missing_repos: bool,
+  repo_manager: &RepositoryManager,
+) -> Result<ModpackImportResult> {
+  let configured = load_config()?.repos;
+  let missing_repositories: Vec<String> = modpack
+    .repositories
+    .iter()
+    .filter(|url| !configured.iter().any(|repo| repo.url == **url))
+    .cloned()
+    .collect();
+
+  if !missing_repositories.is_empty() && !add_missing_repos {
+    return Ok(ModpackImportResult {
+      needs_confirmation: true,
+      missing_repositories,
+      ..Default::default()
+    });
+  }
+
+  let mut result = ModpackImportResult {
+    missing_repositories: missing_repositories.clone(),
+    ..Default::default()
+  };
+
+  for url in missing_repositories {
+    add_repo(RepoEntry::new(url.clone()))?;
+    if let Err(e) = repo_manager.fetch_repository(&url).await {
+      result
+        .warnings
+        .push(format!("Failed to fetch repository {}: {}", url, e));
+    }
+  }
+
+  let catalog = load_catalog(repo_manager).await?;
+
+  for entry in &modpack.mods {
+    let source = match catalog_download_url(&catalog, entry) {
+      Some(url) => catalog.download_source(&entry.mod_id, &url),
+      None => {
+        result.warnings.push(format!(
+          "{} {} is not in any configured repository, installing from its recorded URL",
+          entry.mod_id, entry.version
+        ));
+        DownloadSource::remote(&entry.download_url)
+      }
+    };
+
+    match install_modpack_mod(entry, &source, game_path).await {
+      Ok(()) => result.installed.push(entry.mod_id.clone()),
+      Err(error) => {
+        tracing::warn!(mod_id = %entry.mod_id, %error, "Modpack mod failed to install");
+        result.failed.push(ModActionFailure {
+          mod_id: entry.mod_id.clone(),
+          error,
+        })
+      }
+    }
+  }
+
+  result.config_files_written = write_config_files(game_path, &modpack.config_files)?;
+
+  Ok(result)
+}
+
+/// Get where the catalog offers the exact version of a modpack entry
+fn catalog_download_url(catalog: &Catalog, entry: &ModpackMod) -> Option<String> {
+  catalog
+    .find(&entry.mod_id)?
+    .definition
+    .downloads_for_version(&entry.version)?
+    .first()
+    .map(|download| download.url.clone())
+}
+
+/// Download, verify and install one modpack entry
+async fn install_modpack_mod(
+  entry: &ModpackMod,
+  source: &DownloadSource,
+  game_path: &Path,
+) -> Result<()> {
+  let cached_file = fetch_cached_download(source).await?;
+  let checksum = file_sha256(&cached_file)?;
+
+  if !checksum.eq_ignore_ascii_case(&entry.sha256) {
+    // Drop the bad download so a retry fetches it again
+    let _ = fs::remove_file(&cached_file);
+    return Err(SilkSpoolError::ChecksumMismatch {
+      mod_id: entry.mod_id.clone(),
+      expected: entry.sha256.clone(),
+      actual: checksum,
+    });
+  }
+
+  install_tracked_version(
+    &entry.mod_id,
+    &entry.title,
+    &entry.version,
+    source,
+    game_path,
+    entry.enabled,
+    ConflictPolicy::Abort,
+  )
+  .await
+}
+
+/// Get the BepInEx config directory of a game install
+fn bepinex_config_dir(game_path: &Path) -> PathBuf {
+  game_path.join("BepInEx").join("config")
+}
+
+/// Read every BepInEx `.cfg` file of a game install
+fn collect_config_files(game_path: &Path) -> Result<Vec<ModpackConfigFile>> {
+  let config_dir = bepinex_config_dir(game_path);
+  let mut files = Vec::new();
+
+  if !config_dir.exists() {
+    return Ok(files);
+  }
+
+  for entry in WalkDir::new(&config_dir).into_iter().flatten() {
+    let path = entry.path();
+    if !entry.file_type().is_file() || path.extension().and_then(|e| e.to_str()) != Some("cfg") {
+      continue;
+    }
+
+    let relative = path
+      .strip_prefix(&config_dir)
+      .map_err(|e| SilkSpoolError::io("resolve config path", path, e))?;
+    let contents =
+      fs::read_to_string(path).map_err(|e| SilkSpoolError::io("read config file", path, e))?;
+
+    files.push(ModpackConfigFile {
+      path: relative.to_string_lossy().replace('\\', "/"),
+      contents,
+    });
+  }
+
+  Ok(files)
+}
+
+/// Write bundled config files into the BepInEx config directory
+fn write_config_files(game_path: &Path, files: &[ModpackConfigFile]) -> Result<usize> {
+  let config_dir = bepinex_config_dir(game_path);
+
+  for file in files {
+    let relative = Path::new(&file.path);
+
+    // Never let a modpack write outside the config directory
+    if !relative
+      .components()
+      .all(|c| matches!(c, Component::Normal(_)))
+    {
+      return Err(SilkSpoolError::invalid_input(format!(
+        "Invalid config file path in modpack: {}",
+        file.path
+      )));
+    }
+
+    let destination = config_dir.join(relative);
+    if let Some(parent) = destination.parent() {
+      fs::create_dir_all(parent)
+        .map_err(|e| SilkSpoolError::io("create config directory", parent, e))?;
+    }
+
+    fs::write(&destination, &file.contents)
+      .map_err(|e| SilkSpoolError::io("write config file", &destination, e))?;
+  }
+
+  Ok(files.len())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::schema::{ModRepository, SCHEMA_VERSION};
+
+  #[test]
+  fn test_config_files_round_trip() {
+    let source = tempfile::tempdir().unwrap();
+    let config_dir = bepinex_config_dir(source.path());
+    fs::create_dir_all(config_dir.join("nested")).unwrap();
+    fs::write(config_dir.join("BepInEx.cfg"), "[Logging]").unwrap();
+    fs::write(config_dir.join("nested").join("mod.cfg"), "[General]").unwrap();
+    fs::write(config_dir.join("notes.txt"), "ignored").unwrap();
+
+    let mut files = collect_config_files(source.path()).unwrap();
+    files.sort_by(|a, b| a.path.cmp(&b.path));
+    let paths: Vec<&str> = files.iter().map(|f| f.path.as_str()).collect();
+    assert_eq!(paths, vec!["BepInEx.cfg", "nested/mod.cfg"]);
+
+    let target = tempfile::tempdir().unwrap();
+    assert_eq!(write_config_files(target.path(), &files).unwrap(), 2);
+    assert_eq!(
+      fs::read_to_string(bepinex_config_dir(target.path()).join("nested/mod.cfg")).unwrap(),
+      "[General]"
+    );
+  }
+
+  #[test]
+  fn test_write_config_files_rejects_traversal() {
+    let target = tempfile::tempdir().unwrap();
+    let files = vec![ModpackConfigFile {
+      path: "../../evil.cfg".to_string(),
+      contents: String::new(),
+    }];
+
+    assert!(write_config_files(target.path(), &files).is_err());
+  }
+
+  #[tokio::test]
+  async fn test_install_modpack_mod_rejects_a_title_outside_the_plugins_folder() {
+    let dirs = crate::config::test_dirs::isolate();
+    let game_path = dirs.path().join("game");
+    fs::create_dir_all(game_path.join("BepInEx/plugins")).unwrap();
+    let victim = dirs.path().join("victim").join("keep.txt");
+    fs::create_dir_all(victim.parent().unwrap()).unwrap();
+    fs::write(&victim, b"keep").unwrap();
+    let download = dirs.path().join("payload.txt");
+    fs::write(&download, b"payload").unwrap();
+
+    let entry = ModpackMod {
+      mod_id: "hostile".to_string(),
+      title: "../../../victim".to_string(),
+      version: "1.0.0".to_string(),
+      enabled: true,
+      repo_id: None,
+      download_url: String::new(),
+      sha256: file_sha256(&download).unwrap(),
+    };
+    let source = DownloadSource {
+      url: reqwest::Url::from_file_path(&download).unwrap().to_string(),
+      local_root: Some(dirs.path().to_path_buf()),
+    };
+    let result = install_modpack_mod(&entry, &source, &game_path).await;
+
+    assert!(matches!(result, Err(SilkSpoolError::InvalidInput { .. })));
+    assert_eq!(fs::read(&victim).unwrap(), b"keep");
+  }
+
+  #[test]
+  fn test_catalog_download_url_prefers_the_repository() {
+    let definition = serde_json::from_str(
+      r#"{
+        "id": "a",
+        "title": "A",
+        "version": "2.0.0",
+        "description": "Test mod",
+        "requirements": [],
+        "images": [],
+        "downloads": [{"label": "Download", "url": "https://mirror.example.com/a-2.zip"}],
+        "homepage": null,
+        "authors": [],
+        "game_version": "1.0",
+        "updated_at": "2024-01-01",
+        "versions": [{"version": "1.0.0", "downloads": [{"label": "Old", "url": "https://mirror.example.com/a-1.zip"}]}]
+      }"#,
+    )
+    .unwrap();
+    let repository = ModRepository {
+      schema_version: SCHEMA_VERSION,
+      repo_id: "main".to_string(),
+      name: "Main".to_string(),
+      version: 1,
+      mods: vec![definition],
+      extra: Default::default(),
+    };
+    let catalog = Catalog::build(
+      vec![(
+        repository,
+        "https://mirror.example.com/mods.json".to_string(),
+      )],
+      &[],
+    );
+    let entry = |mod_id: &str, version: &str| ModpackMod {
+      mod_id: mod_id.to_string(),
+      title: mod_id.to_string(),
+      version: version.to_string(),
+      enabled: true,
+      repo_id: Some("main".to_string()),
+      download_url: "https://old.example.com/a.zip".to_string(),
+      sha256: String::new(),
+    };
+
+    assert_eq!(
+      catalog_download_url(&catalog, &entry("a", "1.0.0")).as_deref(),
+      Some("https://mirror.example.com/a-1.zip")
+    );
+    assert_eq!(catalog_download_url(&catalog, &entry("a", "3.0.0")), None);
+    assert_eq!(catalog_download_url(&catalog, &entry("b", "1.0.0")), None);
+  }
+}
@@ -0,0 +1,495 @@
+use std::{collections::BTreeMap, fs, path::Path};
+
+use serde::{Deserialize, Deserializer, Serialize};
+
+use crate::{
+  catalog::Catalog,
+  config::get_app_data_dir,
+  conflicts::ConflictPolicy,
+  error::{Result, SilkSpoolError},
+  installed_mods::get_installed_mods_for_game,
+  installer::{disable_mod, enable_mod, install_tracked_version, uninstall_tracked_mod},
+  types::{InstalledMod, ModActionFailure},
+};
+
+/// A mod entry inside a profile
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProfileMod {
+  pub mod_id: String,
+  pub title: String,
+  pub version: String,
+  pub enabled: bool,
+  pub download_url: Option<String>,
+}
+
+/// A named set of mods for one game install
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Profile {
+  pub name: String,
+  pub mods: Vec<ProfileMod>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ProfilesData {
+  /// The profile each game directory was last fully switched to, keyed by game path
+  #[serde(default, deserialize_with = "deserialize_active")]
+  pub active: BTreeMap<String, String>,
+  pub profiles: Vec<Profile>,
+}
+
+impl ProfilesData {
+  /// Get the profile a game directory was last fully switched to
+  pub fn active_for(&self, game_path: &Path) -> Option<&str> {
+    self
+      .active
+      .iter()
+      .find(|(path, _)| Path::new(path) == game_path)
+      .map(|(_, name)| name.as_str())
+  }
+}
+
+/// Read the active profiles, dropping the single game-less entry older versions stored
+fn deserialize_active<'de, D>(deserializer: D) -> Result<BTreeMap<String, String>, D::Error>
+where
+  D: Deserializer<'de>,
+{
+  #[derive(Deserialize)]
+  #[serde(untagged)]
+  enum StoredActive {
+    PerGame(BTreeMap<String, String>),
+    Single(Option<String>),
+  }
+
+  Ok(match StoredActive::deserialize(deserializer)? {
+    StoredActive::PerGame(active) => active,
+    StoredActive::Single(Some(name)) => {
+      tracing::info!(profile = %name, "Dropping an active profile saved without a game path");
+      BTreeMap::new()
+    }
+    StoredActive::Single(None) => BTreeMap::new(),
+  })
+}
+
+/// The changes needed to go from the installed mods to a profile
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ProfileSwitchPlan {
+  pub profile: String,
+  pub install: Vec<ProfileMod>,
+  pub remove: Vec<String>,
+  pub enable: Vec<String>,
+  pub disable: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ProfileSwitchResult {
+  pub profile: String,
+  pub installed: Vec<String>,
+  pub removed: Vec<String>,
+  pub enabled: Vec<String>,
+  pub disabled: Vec<String>,
+  pub failed: Vec<ModActionFailure>,
+}
+
+/// Get the profiles file path, next to the configuration file
+fn get_profiles_path() -> Result<std::path::PathBuf> {
+  Ok(get_app_data_dir()?.join("profiles.json"))
+}
+
+/// Load profiles from storage
+pub fn load_profiles() -> Result<ProfilesData> {
+  let path = get_profiles_path()?;
+
+  if !path.exists() {
+    return Ok(ProfilesData::default());
+  }
+
+  let content =
+    fs::read_to_string(&path).map_err(|e| SilkSpoolError::io("read profiles file", &path, e))?;
+
+  serde_json::from_str(&content).map_err(|e| SilkSpoolError::parse("profiles file", e))
+}
+
+/// Save profiles to storage
+pub fn save_profiles(data: &ProfilesData) -> Result<()> {
+  let path = get_profiles_path()?;
+
+  if let Some(parent) = path.parent() {
+    fs::create_dir_all(parent)
+      .map_err(|e| SilkSpoolError::io("create app directory", parent, e))?;
+  }
+
+  let content = serde_json::to_string_pretty(data)
+    .map_err(|e| SilkSpoolError::other(format!("Failed to serialize profiles: {}", e)))?;
+
+  fs::write(&path, content).map_err(|e| SilkSpoolError::io("write profiles file", &path, e))
+}
+
+/// Create or replace a profile
+pub fn save_profile(profile: Profile) -> Result<()> {
+  if profile.name.trim().is_empty() {
+    return Err(SilkSpoolError::invalid_input(
+      "Profile name cannot be empty",
+    ));
+  }
+
+  let mut data = load_profiles()?;
+  data.profiles.retain(|p| p.name != profile.name);
+  data.profiles.push(profile);
+
+  save_profiles(&data)
+}
+
+/// Delete a profile
+pub fn delete_profile(name: &str) -> Result<()> {
+  let mut data = load_profiles()?;
+
+  data.profiles.retain(|p| p.name != name);
+  data.active.retain(|_, active| active != name);
+
+  save_profiles(&data)
+}
+
+/// Get a profile by name
+pub fn get_profile(name: &str) -> Result<Profile> {
+  load_profiles()?
+    .profiles
+    .into_iter()
+    .find(|p| p.name == name)
+    .ok_or_else(|| SilkSpoolError::ProfileNotFound {
+      name: name.to_string(),
+    })
+}
+
+/// Describe the mods installed in a game directory right now as profile entries
+pub fn installed_profile_mods(game_path: &Path) -> Result<Vec<ProfileMod>> {
+  Ok(
+    get_installed_mods_for_game(game_path)?
+      .into_iter()
+      .map(|installed_mod| ProfileMod {
+        mod_id: installed_mod.mod_id,
+        title: installed_mod.mod_title,
+        version: installed_mod.version,
+        enabled: installed_mod.enabled,
+        download_url: installed_mod.download_url,
+      })
+      .collect(),
+  )
+}
+
+/// Build a profile from the mods installed in a game directory right now
+pub fn snapshot_profile(name: &str, game_path: &Path) -> Result<Profile> {
+  let profile = Profile {
+    name: name.to_string(),
+    mods: installed_profile_mods(game_path)?,
+  };
+  save_profile(profile.clone())?;
+
+  Ok(profile)
+}
+
+/// Diff a profile against the installed mods
+pub fn plan_profile_switch(profile: &Profile, installed: &[InstalledMod]) -> ProfileSwitchPlan {
+  let mut plan = ProfileSwitchPlan {
+    profile: profile.name.clone(),
+    ..Default::default()
+  };
+
+  for wanted in &profile.mods {
+    match installed.iter().find(|m| m.mod_id == wanted.mod_id) {
+      Some(current) if current.version == wanted.version => {
+        if current.enabled && !wanted.enabled {
+          plan.disable.push(wanted.mod_id.clone());
+        } else if !current.enabled && wanted.enabled {
+          plan.enable.push(wanted.mod_id.clone());
+        }
+      }
+      _ => plan.install.push(wanted.clone()),
+    }
+  }
+
+  for current in installed {
+    if !profile.mods.iter().any(|m| m.mod_id == current.mod_id) {
+      plan.remove.push(current.mod_id.clone());
+    }
+  }
+
+  plan
+}
+
+/// Make the mods installed in a game directory match a profile
+///
+/// The profile is marked active for this game directory only when every step succeeds. After a
+/// partial switch the mods match no profile, so none is marked active for it. Missing mods are
+/// downloaded from the repository `catalog` lists them in.
+#[tracing::instrument(skip(game_path, catalog), err)]
+pub async fn switch_profile(
+  name: &str,
+  game_path: &Path,
+  catalog: &Catalog,
+) -> Result<ProfileSwitchResult> {
+  let profile = get_profile(name)?;
+  let plan = plan_profile_switch(&profile, &get_installed_mods_for_game(game_path)?);
+  tracing::info!(
+    remove = plan.remove.len(),
+    disable = plan.disable.len(),
+    enable = plan.enable.len(),
+    install = plan.install.len(),
+    "Switching profile"
+  );
+
+  let mut result = ProfileSwitchResult {
+    profile: profile.name.clone(),
+    ..Default::default()
+  };
+
+  // Clear out unwanted mods first so they cannot clash with the ones being installed
+  for mod_id in &plan.remove {
+    let outcome = uninstall_tracked_mod(mod_id, game_path).map(|_| ());
+    record_outcome(mod_id, outcome, &mut result.removed, &mut result.failed);
+  }
+
+  for mod_id in &plan.disable {
+    let outcome = disable_mod(mod_id, game_path).map(|_| ());
+    record_outcome(mod_id, outcome, &mut result.disabled, &mut result.failed);
+  }
+
+  for mod_id in &plan.enable {
+    let outcome = enable_mod(mod_id, game_path).and_then(|enabled| {
+      if enabled.success {
+        Ok(())
+      } else {
+        Err(SilkSpoolError::FileConflict {
+          mod_id: mod_id.clone(),
+          message: enabled.message,
+        })
+      }
+    });
+    record_outcome(mod_id, outcome, &mut result.enabled, &mut result.failed);
+  }
+
+  for wanted in &plan.install {
+    let outcome = install_profile_mod(wanted, game_path, catalog).await;
+    record_outcome(
+      &wanted.mod_id,
+      outcome,
+      &mut result.installed,
+      &mut result.failed,
+    );
+  }
+
+  let mut data = load_profiles()?;
+  data.active.retain(|path, _| Path::new(path) != game_path);
+  if result.failed.is_empty() {
+    data
+      .active
+      .insert(game_path.to_string_lossy().to_string(), profile.name);
+  }
+  save_profiles(&data)?;
+
+  Ok(result)
+}
+
+/// Sort the outcome of one switch step into the done or failed list
+fn record_outcome(
+  mod_id: &str,
+  outcome: Result<()>,
+  done: &mut Vec<String>,
+  failed: &mut Vec<ModActionFailure>,
+) {
+  match outcome {
+    Ok(()) => done.push(mod_id.to_string()),
+    Err(error) => {
+      tracing::warn!(mod_id, %error, "Profile switch step failed");
+      failed.push(ModActionFailure {
+        mod_id: mod_id.to_string(),
+        error,
+      })
+    }
+  }
+}
+
+/// Install one profile entry, reusing the download cache
+async fn install_profile_mod(
+  wanted: &ProfileMod,
+  game_path: &Path,
+  catalog: &Catalog,
+) -> Result<()> {
+  let download_url =
+    wanted
+      .download_url
+      .as_deref()
+      .ok_or_else(|| SilkSpoolError::DownloadUnavailable {
+        mod_id: wanted.mod_id.clone(),
+        message: format!("No download URL recorded for {}", wanted.mod_id),
+      })?;
+
+  install_tracked_version(
+    &wanted.mod_id,
+    &wanted.title,
+    &wanted.version,
+    &catalog.download_source(&wanted.mod_id, download_url),
+    game_path,
+    wanted.enabled,
+    ConflictPolicy::Abort,
+  )
+  .await
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::{
+    config::test_dirs,
+    installed_mods::{add_installed_mod, get_installed_mod},
+    installer::DownloadSource,
+  };
+
+  fn installed(mod_id: &str, version: &str, enabled: bool) -> InstalledMod {
+    InstalledMod {
+      mod_id: mod_id.to_string(),
+      mod_title: mod_id.to_string(),
+      version: version.to_string(),
+      installed_at: "2024-01-01T00:00:00Z".to_string(),
+      installed_files: vec![],
+      game_path: "/game".to_string(),
+      download_url: None,
+      enabled,
+      pinned_version: None,
+      history: vec![],
+    }
+  }
+
+  fn wanted(mod_id: &str, version: &str, enabled: bool) -> ProfileMod {
+    ProfileMod {
+      mod_id: mod_id.to_string(),
+      title: mod_id.to_string(),
+      version: version.to_string(),
+      enabled,
+      download_url: Some(format!("https://example.com/{}.zip", mod_id)),
+    }
+  }
+
+  #[test]
+  fn test_plan_profile_switch() {
+    let profile = Profile {
+      name: "speedrun".to_string(),
+      mods: vec![
+        wanted("same", "1.0.0", true),
+        wanted("to-disable", "1.0.0", false),
+        wanted("to-enable", "1.0.0", true),
+        wanted("upgrade", "2.0.0", true),
+        wanted("missing", "1.0.0", true),
+      ],
+    };
+
+    let installed = vec![
+      installed("same", "1.0.0", true),
+      installed("to-disable", "1.0.0", true),
+      installed("to-enable", "1.0.0", false),
+      installed("upgrade", "1.0.0", true),
+      installed("extra", "1.0.0", true),
+    ];
+
+    let plan = plan_profile_switch(&profile, &installed);
+
+    assert_eq!(plan.disable, vec!["to-disable".to_string()]);
+    assert_eq!(plan.enable, vec!["to-enable".to_string()]);
+    assert_eq!(plan.remove, vec!["extra".to_string()]);
+    let install: Vec<&str> = plan.install.iter().map(|m| m.mod_id.as_str()).collect();
+    assert_eq!(install, vec!["upgrade", "missing"]);
+  }
+
+  #[tokio::test]
+  async fn test_partial_switch_leaves_other_games_alone_and_no_profile_active() {
+    let _dirs = test_dirs::isolate();
+    let game_dir = tempfile::tempdir().unwrap();
+    let other_game_dir = tempfile::tempdir().unwrap();
+    add_installed_mod(
+      "elsewhere".to_string(),
+      "Elsewhere".to_string(),
+      "1.0.0".to_string(),
+      vec![],
+      other_game_dir.path().to_string_lossy().to_string(),
+      None,
+    )
+    .unwrap();
+    let mut unavailable = wanted("unavailable", "1.0.0", true);
+    unavailable.download_url = None;
+    save_profile(Profile {
+      name: "broken".to_string(),
+      mods: vec![unavailable],
+    })
+    .unwrap();
+
+    let result = switch_profile("broken", game_dir.path(), &Catalog::default())
+      .await
+      .unwrap();
+
+    assert!(result.removed.is_empty());
+    assert_eq!(result.failed.len(), 1);
+    assert_eq!(result.failed[0].mod_id, "unavailable");
+    assert_eq!(
+      get_installed_mods_for_game(other_game_dir.path())
+        .unwrap()
+        .len(),
+      1
+    );
+    assert_eq!(load_profiles().unwrap().active_for(game_dir.path()), None);
+  }
+
+  #[tokio::test]
+  async fn test_switch_only_touches_the_mods_and_active_profile_of_its_game() {
+    let dirs = test_dirs::isolate();
+    let plugin = dirs.path().join("Shared.dll");
+    fs::write(&plugin, b"shared").unwrap();
+    let source = DownloadSource {
+      url: reqwest::Url::from_file_path(&plugin).unwrap().to_string(),
+      local_root: Some(dirs.path().to_path_buf()),
+    };
+    let games = [tempfile::tempdir().unwrap(), tempfile::tempdir().unwrap()];
+    for game in &games {
+      fs::create_dir_all(game.path().join("BepInEx/core")).unwrap();
+      fs::write(game.path().join("BepInEx/core/BepInEx.dll"), b"core").unwrap();
+      install_tracked_version(
+        "shared",
+        "Shared",
+        "1.0.0",
+        &source,
+        game.path(),
+        true,
+        ConflictPolicy::Abort,
+      )
+      .await
+      .unwrap();
+    }
+    save_profile(Profile {
+      name: "vanilla".to_string(),
+      mods: vec![],
+    })
+    .unwrap();
+
+    let result = switch_profile("vanilla", games[1].path(), &Catalog::default())
+      .await
+      .unwrap();
+
+    assert_eq!(result.removed, vec!["shared".to_string()]);
+    assert!(get_installed_mod("shared", games[1].path())
+      .unwrap()
+      .is_none());
+    let kept = get_installed_mod("shared", games[0].path())
+      .unwrap()
+      .unwrap();
+    assert_eq!(fs::read(&kept.installed_files[0]).unwrap(), b"shared");
+
+    let data = load_profiles().unwrap();
+    assert_eq!(data.active_for(games[1].path()), Some("vanilla"));
+    assert_eq!(data.active_for(games[0].path()), None);
+  }
+
+  #[test]
+  fn test_legacy_active_profile_is_dropped() {
+    let data: ProfilesData =
+      serde_json::from_str(r#"{"active": "speedrun", "profiles": []}"#).unwrap();
+    assert!(data.active.is_empty());
+  }
+}
@@ -0,0 +1,1126 @@
+use std::path::{Path, PathBuf};
+
+use minisign_verify::{PublicKey, Signature};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tokio::{fs as async_fs, io::AsyncReadExt};
+
+use crate::{
+  config::{find_repo, get_app_cache_dir, is_offline, load_config, RepoAuth},
+  error::{Result, SilkSpoolError},
+  schema::{deserialize_repository, parse_repository, ModRepository},
+  validation::{validate_repository, ValidationReport},
+};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RepositoryInfo {
+  pub id: String,
+  pub name: String,
+  pub url: String,
+  pub version: u32,
+  pub last_updated: Option<String>,
+  pub mod_count: usize,
+}
+
+/// What the server told us about a cached repository the last time it was fetched
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct FetchMetadata {
+  pub url: String,
+  pub etag: Option<String>,
+  pub last_modified: Option<String>,
+  pub fetched_at: String,
+  /// The pinned key the cached copy was verified with
+  #[serde(default)]
+  pub signed_with: Option<String>,
+}
+
+/// The on-disk form of a cached repository
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct CachedRepository {
+  #[serde(flatten)]
+  source: FetchMetadata,
+  #[serde(deserialize_with = "deserialize_repository")]
+  repository: ModRepository,
+}
+
+/// A repository as returned by the offline-first lookup
+#[derive(Debug, Clone, Serialize)]
+pub struct RepositoryResult {
+  pub repository: ModRepository,
+  /// True when the cached copy was returned because the repository could not be refreshed
+  pub stale: bool,
+  pub fetched_at: Option<String>,
+  /// Why the refresh failed, for stale results
+  pub error: Option<SilkSpoolError>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RepositoryResponse {
+  pub success: bool,
+  pub data: Option<ModRepository>,
+  pub error: Option<SilkSpoolError>,
+}
+
+/// Get the directory where fetched repositories are cached
+pub fn get_repository_cache_dir() -> PathBuf {
+  get_app_cache_dir()
+    .unwrap_or_else(|_| std::env::temp_dir().join("silk-spool"))
+    .join("repositories")
+}
+
+/// Check that a repository ID only uses letters, digits, '-', '_' and '.'
+pub fn validate_repo_id(repo_id: &str) -> Result<()> {
+  let valid = !repo_id.is_empty()
+    && repo_id.len() <= 64
+    && !repo_id.starts_with('.')
+    && repo_id
+      .chars()
+      .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'));
+
+  if valid {
+    Ok(())
+  } else {
+    Err(SilkSpoolError::InvalidRepository {
+      message: format!(
+        "Invalid repository ID '{}': only letters, digits, '-', '_' and '.' are allowed",
+        repo_id
+      ),
+    })
+  }
+}
+
+/// Get the file to read for a local repository, or `None` for remote URLs
+///
+/// Local repositories are given as `file://` URLs or plain paths; a directory stands for the
+/// `mods.json` file inside it.
+fn local_repository_path(location: &str) -> Result<Option<PathBuf>> {
+  let path = if location.starts_with("file://") {
+    reqwest::Url::parse(location)
+      .ok()
+      .and_then(|url| url.to_file_path().ok())
+      .ok_or_else(|| SilkSpoolError::invalid_input(format!("Invalid file URL: {}", location)))?
+  } else if location.contains("://") {
+    return Ok(None);
+  } else {
+    let path = PathBuf::from(location);
+    if !path.exists() {
+      return Ok(None);
+    }
+    path
+  };
+
+  if path.is_dir() {
+    Ok(Some(path.join("mods.json")))
+  } else {
+    Ok(Some(path))
+  }
+}
+
+/// Get the directory of a local repository, or `None` for remote ones
+pub fn local_repository_dir(location: &str) -> Option<PathBuf> {
+  local_repository_path(location)
+    .ok()??
+    .parent()
+    .map(Path::to_path_buf)
+}
+
+/// Guess which configured repository a bare legacy cache file was fetched from
+///
+/// A local repository matches when its file has the same ID. Otherwise the only configured
+/// remote repository without a cached copy of its own is taken; with several candidates the
+/// file keeps no URL until its repository is fetched again.
+fn legacy_source_url(repo_id: &str, cached_urls: &[String]) -> Option<String> {
+  let candidates: Vec<String> = load_config()
+    .ok()?
+    .repos
+    .into_iter()
+    .map(|repo| repo.url)
+    .filter(|url| !cached_urls.contains(url))
+    .collect();
+
+  let mut remote = Vec::new();
+  for url in candidates {
+    match local_repository_path(&url) {
+      Ok(Some(path)) => {
+        let local_id = std::fs::read_to_string(&path)
+          .ok()
+          .and_then(|contents| parse_repository(&contents).ok())
+          .map(|repository| repository.repo_id);
+        if local_id.as_deref() == Some(repo_id) {
+          return Some(url);
+        }
+      }
+      Ok(None) => remote.push(url),
+      Err(_) => {}
+    }
+  }
+
+  match remote.as_slice() {
+    [url] => Some(url.clone()),
+    _ => None,
+  }
+}
+
+/// Get the URL that paths inside a repository are relative to
+///
+/// For remote repositories this is the repository URL itself; for local ones it is the directory
+/// holding the repository file.
+pub fn repository_base_url(location: &str) -> Option<reqwest::Url> {
+  match local_repository_path(location).ok()? {
+    Some(path) => path
+      .canonicalize()
+      .ok()?
+      .parent()
+      .and_then(|dir| reqwest::Url::from_directory_path(dir).ok()),
+    None => reqwest::Url::parse(location).ok(),
+  }
+}
+
+/// Resolve a URL given relative to a repository, leaving absolute URLs untouched
+pub fn resolve_repository_url(base: &reqwest::Url, url: &str) -> String {
+  if url.is_empty() || reqwest::Url::parse(url).is_ok() {
+    return url.to_string();
+  }
+
+  base
+    .join(url)
+    .map(|resolved| resolved.to_string())
+    .unwrap_or_else(|_| url.to_string())
+}
+
+/// Turn download and image URLs given relative to the repository into absolute ones
+fn resolve_relative_urls(repository: &mut ModRepository, base: &reqwest::Url) {
+  for mod_item in &mut repository.mods {
+    let downloads = mod_item.downloads.iter_mut().chain(
+      mod_item
+        .versions
+        .iter_mut()
+        .flat_map(|v| v.downloads.iter_mut()),
+    );
+    for download in downloads {
+      download.url = resolve_repository_url(base, &download.url);
+    }
+
+    for image in &mut mod_item.images {
+      *image = resolve_repository_url(base, image);
+    }
+  }
+}
+
+/// Start a GET request for a repository, with the credentials configured for it
+fn repository_request(url: &str) -> reqwest::RequestBuilder {
+  let request = reqwest::Client::new().get(url);
+
+  match find_repo(url).and_then(|repo| repo.auth) {
+    Some(RepoAuth::Bearer { token }) => request.bearer_auth(token),
+    Some(RepoAuth::Basic { username, password }) => request.basic_auth(username, Some(password)),
+    None => request,
+  }
+}
+
+/// Get the minisign key configured for a repository
+fn pinned_key(url: &str) -> Option<String> {
+  find_repo(url).and_then(|repo| repo.pinned_key)
+}
+
+/// Check a repository document against the detached minisign signature made with `pinned_key`
+///
+/// The key may be given as the base64 line alone or as the whole `.pub` file.
+fn verify_signature(url: &str, document: &str, signature: &str, pinned_key: &str) -> Result<()> {
+  let mismatch = |message: String| SilkSpoolError::SignatureMismatch {
+    url: url.to_string(),
+    message,
+  };
+
+  let key = PublicKey::decode(pinned_key)
+    .or_else(|_| PublicKey::from_base64(pinned_key.trim()))
+    .map_err(|e| mismatch(format!("invalid pinned key: {}", e)))?;
+  let signature =
+    Signature::decode(signature).map_err(|e| mismatch(format!("invalid signature: {}", e)))?;
+
+  key
+    .verify(document.as_bytes(), &signature, false)
+    .map_err(|e| mismatch(e.to_string()))
+}
+
+/// Fetch the `.minisig` file published next to a remote repository
+async fn fetch_signature(url: &str) -> Result<String> {
+  let signature_url = format!("{}.minisig", url);
+  let response = repository_request(&signature_url)
+    .send()
+    .await
+    .map_err(|e| SilkSpoolError::network(&signature_url, e))?;
+
+  if !response.status().is_success() {
+    return Err(SilkSpoolError::SignatureMismatch {
+      url: url.to_string(),
+      message: format!("no signature at {} ({})", signature_url, response.status()),
+    });
+  }
+
+  response
+    .text()
+    .await
+    .map_err(|e| SilkSpoolError::network(&signature_url, e))
+}
+
+/// Check that a repository answers, without downloading or caching it
+pub async fn check_repository_reachable(location: &str) -> Result<()> {
+  if let Some(path) = local_repository_path(location)? {
+    if !path.is_file() {
+      return Err(SilkSpoolError::io(
+        "read repository file",
+        &path,
+        "file not found",
+      ));
+    }
+    return Ok(());
+  }
+
+  if !location.starts_with("http://") && !location.starts_with("https://") {
+    return Err(SilkSpoolError::invalid_input(format!(
+      "Invalid repository location {}",
+      location
+    )));
+  }
+
+  if is_offline() {
+    return Err(SilkSpoolError::Offline {
+      url: location.to_string(),
+    });
+  }
+
+  // Only the status is needed, so the body is never read
+  let response = repository_request(location)
+    .timeout(std::time::Duration::from_secs(10))
+    .send()
+    .await
+    .map_err(|e| SilkSpoolError::network(location, e))?;
+
+  if !response.status().is_success() {
+    return Err(SilkSpoolError::HttpStatus {
+      url: location.to_string(),
+      status: response.status().as_u16(),
+    });
+  }
+
+  Ok(())
+}
+
+pub struct RepositoryManager {
+  cache_dir: String,
+}
+
+impl RepositoryManager {
+  pub fn new(cache_dir: String) -> Self {
+    Self { cache_dir }
+  }
+
+  /// Fetch mod data from a repository URL
+  ///
+  /// When the repository is cached, the request is made conditional on the stored ETag and
+  /// Last-Modified values, and a `304 Not Modified` answer returns the cached copy.
+  #[tracing::instrument(skip(self), err)]
+  pub async fn fetch_repository(&self, url: &str) -> Result<ModRepository> {
+    if let Some(path) = local_repository_path(url)? {
+      return self.read_local_repository(url, &path).await;
+    }
+
+    // Validate URL format
+    if !url.starts_with("http://") && !url.starts_with("https://") {
+      return Err(SilkSpoolError::invalid_input(
+        "Invalid repository location. Use an http(s) URL, a file:// URL or a local path",
+      ));
+    }
+
+    if is_offline() {
+      return Err(SilkSpoolError::Offline {
+        url: url.to_string(),
+      });
+    }
+
+    let cached = self.find_cached_by_url(url).await;
+
+    // Make HTTP request
+    let mut request = repository_request(url);
+    if let Some(entry) = &cached {
+      if let Some(etag) = &entry.source.etag {
+        request = request.header(reqwest::header::IF_NONE_MATCH, etag);
+      }
+      if let Some(last_modified) = &entry.source.last_modified {
+        request = request.header(reqwest::header::IF_MODIFIED_SINCE, last_modified);
+      }
+    }
+
+    let response = request
+      .send()
+      .await
+      .map_err(|e| SilkSpoolError::network(url, e))?;
+
+    if response.status() == reqwest::StatusCode::NOT_MODIFIED {
+      if let Some(mut entry) = cached {
+        tracing::info!("Repository not modified, using cached copy");
+        entry.source.fetched_at = chrono::Utc::now().to_rfc3339();
+        self.cache_repository(&entry).await?;
+        return Ok(entry.repository);
+      }
+    }
+
+    if !response.status().is_success() {
+      return Err(SilkSpoolError::HttpStatus {
+        url: url.to_string(),
+        status: response.status().as_u16(),
+      });
+    }
+
+    let header = |name: reqwest::header::HeaderName| {
+      response
+        .headers()
+        .get(name)
+        .and_then(|value| value.to_str().ok())
+        .map(|value| value.to_string())
+    };
+    let mut source = FetchMetadata {
+      url: url.to_string(),
+      etag: header(reqwest::header::ETAG),
+      last_modified: header(reqwest::header::LAST_MODIFIED),
+      fetched_at: chrono::Utc::now().to_rfc3339(),
+      signed_with: None,
+    };
+
+    let json_text = response
+      .text()
+      .await
+      .map_err(|e| SilkSpoolError::network(url, e))?;
+
+    // Repositories with a pinned key must come with a matching signature
+    if let Some(key) = pinned_key(url) {
+      let signature = fetch_signature(url).await?;
+      verify_signature(url, &json_text, &signature, &key)?;
+      source.signed_with = Some(key);
+    }
+
+    // Parse JSON
+    let mut repository = parse_repository(&json_text)?;
+
+    // Validate repository structure
+    let base = reqwest::Url::parse(url).ok();
+    validate_repository(&repository, base.as_ref()).into_result()?;
+
+    // Downloads and images may be given relative to the repository URL
+    if let Some(base) = &base {
+      resolve_relative_urls(&mut repository, base);
+    }
+
+    // Cache the repository together with where it came from
+    let entry = CachedRepository { source, repository };
+    self.cache_repository(&entry).await?;
+    tracing::info!(
+      repo_id = %entry.repository.repo_id,
+      mods = entry.repository.mods.len(),
+      "Fetched repository"
+    );
+
+    Ok(entry.repository)
+  }
+
+  /// Read a repository from a local file, caching it like a fetched one
+  async fn read_local_repository(&self, location: &str, path: &Path) -> Result<ModRepository> {
+    let json_text = async_fs::read_to_string(path)
+      .await
+      .map_err(|e| SilkSpoolError::io("read repository file", path, e))?;
+
+    let signed_with = pinned_key(location);
+    if let Some(key) = &signed_with {
+      let mut signature_path = path.as_os_str().to_owned();
+      signature_path.push(".minisig");
+      let signature = async_fs::read_to_string(&signature_path)
+        .await
+        .map_err(|e| SilkSpoolError::SignatureMismatch {
+          url: location.to_string(),
+          message: format!(
+            "no signature at {}: {}",
+            Path::new(&signature_path).display(),
+            e
+          ),
+        })?;
+      verify_signature(location, &json_text, &signature, key)?;
+    }
+
+    let mut repository = parse_repository(&json_text)?;
+
+    // Downloads and images may be given relative to the repository file
+    let base = repository_base_url(location).ok_or_else(|| {
+      SilkSpoolError::invalid_input(format!(
+        "Failed to resolve repository location {}",
+        path.display()
+      ))
+    })?;
+    validate_repository(&repository, Some(&base)).into_result()?;
+    resolve_relative_urls(&mut repository, &base);
+
+    let entry = CachedRepository {
+      source: FetchMetadata {
+        url: location.to_string(),
+        fetched_at: chrono::Utc::now().to_rfc3339(),
+        signed_with,
+        ..Default::default()
+      },
+      repository,
+    };
+    self.cache_repository(&entry).await?;
+
+    Ok(entry.repository)
+  }
+
+  /// Get a repository, falling back to the cached copy when it cannot be fetched
+  #[tracing::instrument(skip(self))]
+  pub async fn get_repository(&self, url: &str) -> Result<RepositoryResult> {
+    // Within the configured refresh interval the cached copy is used as is
+    let refresh_interval = find_repo(url).and_then(|repo| repo.refresh_interval_minutes);
+    if let Some(minutes) = refresh_interval {
+      if let Some(entry) = self.find_cached_by_url(url).await {
+        let fresh = chrono::DateTime::parse_from_rfc3339(&entry.source.fetched_at)
+          .map(|at| chrono::Utc::now() - at.with_timezone(&chrono::Utc))
+          .is_ok_and(|age| age < chrono::Duration::minutes(minutes as i64));
+
+        if fresh {
+          tracing::debug!("Cached repository is within its refresh interval");
+          return Ok(RepositoryResult {
+            repository: entry.repository,
+            stale: false,
+            fetched_at: Some(entry.source.fetched_at),
+            error: None,
+          });
+        }
+      }
+    }
+
+    let error = match self.fetch_repository(url).await {
+      Ok(repository) => {
+        return Ok(RepositoryResult {
+          repository,
+          stale: false,
+          fetched_at: Some(chrono::Utc::now().to_rfc3339()),
+          error: None,
+        })
+      }
+      Err(error) => error,
+    };
+
+    match self.find_cached_by_url(url).await {
+      Some(entry) => {
+        tracing::warn!(%error, "Using stale cached repository");
+        Ok(RepositoryResult {
+          repository: entry.repository,
+          stale: true,
+          fetched_at: Some(entry.source.fetched_at).filter(|at| !at.is_empty()),
+          error: Some(error),
+        })
+      }
+      None => Err(error),
+    }
+  }
+
+  /// Check a repository without caching it, reporting every problem found
+  ///
+  /// Only failures to read the repository are returned as errors; a document that does not
+  /// parse is reported as a validation error.
+  pub async fn validate_location(&self, location: &str) -> Result<ValidationReport> {
+    let json_text = match local_repository_path(location)? {
+      Some(path) => async_fs::read_to_string(&path)
+        .await
+        .map_err(|e| SilkSpoolError::io("read repository file", &path, e))?,
+      None => {
+        if !location.starts_with("http://") && !location.starts_with("https://") {
+          return Err(SilkSpoolError::invalid_input(
+            "Invalid repository location. Use an http(s) URL, a file:// URL or a local path",
+          ));
+        }
+
+        let response = repository_request(location)
+          .send()
+          .await
+          .map_err(|e| SilkSpoolError::network(location, e))?;
+        if !response.status().is_success() {
+          return Err(SilkSpoolError::HttpStatus {
+            url: location.to_string(),
+            status: response.status().as_u16(),
+          });
+        }
+        response
+          .text()
+          .await
+          .map_err(|e| SilkSpoolError::network(location, e))?
+      }
+    };
+
+    let repository = match parse_repository(&json_text) {
+      Ok(repository) => repository,
+      Err(e) => return Ok(ValidationReport::parse_failure(e.to_string())),
+    };
+
+    Ok(validate_repository(
+      &repository,
+      repository_base_url(location).as_ref(),
+    ))
+  }
+
+  /// Get the path of the cache file for a repository URL
+  ///
+  /// Files are named after a hash of the URL, so repositories that share an ID stay apart.
+  fn cache_path(&self, url: &str) -> PathBuf {
+    Path::new(&self.cache_dir).join(format!("repo_{:x}.json", Sha256::digest(url.as_bytes())))
+  }
+
+  /// Cache repository data to local file
+  async fn cache_repository(&self, entry: &CachedRepository) -> Result<()> {
+    self
+      .write_cache_file(&self.cache_path(&entry.source.url), entry)
+      .await
+  }
+
+  async fn write_cache_file(&self, cache_path: &Path, entry: &CachedRepository) -> Result<()> {
+    // Ensure cache directory exists
+    if let Some(parent) = cache_path.parent() {
+      async_fs::create_dir_all(parent)
+        .await
+        .map_err(|e| SilkSpoolError::io("create cache directory", parent, e))?;
+    }
+
+    let json_data = serde_json::to_string_pretty(entry)
+      .map_err(|e| SilkSpoolError::other(format!("Failed to serialize repository: {}", e)))?;
+
+    async_fs::write(cache_path, json_data)
+      .await
+      .map_err(|e| SilkSpoolError::io("write cache file", cache_path, e))?;
+
+    Ok(())
+  }
+
+  /// Read a cache file, resolving relative URLs against the location it was fetched from
+  ///
+  /// Repositories cached before relative URLs were resolved on fetch are fixed up here.
+  async fn read_cache_entry(&self, cache_path: &Path) -> Result<CachedRepository> {
+    let mut entry = self.read_cache_file(cache_path).await?;
+
+    if let Some(base) = repository_base_url(&entry.source.url) {
+      resolve_relative_urls(&mut entry.repository, &base);
+    }
+
+    Ok(entry)
+  }
+
+  /// Read a cache file, upgrading files that hold a bare repository to the current format
+  async fn read_cache_file(&self, cache_path: &Path) -> Result<CachedRepository> {
+    let mut file = async_fs::File::open(cache_path)
+      .await
+      .map_err(|e| SilkSpoolError::io("open cache file", cache_path, e))?;
+
+    let mut contents = String::new();
+    file
+      .read_to_string(&mut contents)
+      .await
+      .map_err(|e| SilkSpoolError::io("read cache file", cache_path, e))?;
+
+    if let Ok(entry) = serde_json::from_str::<CachedRepository>(&contents) {
+      return Ok(entry);
+    }
+
+    let repository = parse_repository(&contents)?;
+
+    // Older versions cached the bare repository file without any fetch metadata
+    let fetched_at = async_fs::metadata(cache_path)
+      .await
+      .and_then(|metadata| metadata.modified())
+      .map(|time| chrono::DateTime::<chrono::Utc>::from(time).to_rfc3339())
+      .unwrap_or_default();
+
+    let entry = CachedRepository {
+      source: FetchMetadata {
+        fetched_at,
+        ..Default::default()
+      },
+      repository,
+    };
+    self.write_cache_file(cache_path, &entry).await?;
+
+    Ok(entry)
+  }
+
+  /// Read every cached repository
+  ///
+  /// Older versions named cache files after the repository ID; those are moved to the file for
+  /// their URL here, matching bare files to a configured repository first. Corrupted cache files
+  /// are skipped.
+  async fn read_cache_dir(&self) -> Result<Vec<CachedRepository>> {
+    let mut cached = Vec::new();
+    let mut bare = Vec::new();
+
+    if !Path::new(&self.cache_dir).exists() {
+      return Ok(cached);
+    }
+
+    let mut entries = async_fs::read_dir(&self.cache_dir)
+      .await
+      .map_err(|e| SilkSpoolError::io("read cache directory", &self.cache_dir, e))?;
+
+    while let Some(entry) = entries
+      .next_entry()
+      .await
+      .map_err(|e| SilkSpoolError::io("read cache directory", &self.cache_dir, e))?
+    {
+      let path = entry.path();
+      let is_cache_file = path.is_file()
+        && path.extension().and_then(|s| s.to_str()) == Some("json")
+        && path
+          .file_stem()
+          .and_then(|s| s.to_str())
+          .is_some_and(|stem| stem.starts_with("repo_"));
+      if !is_cache_file {
+        continue;
+      }
+
+      let Ok(entry) = self.read_cache_entry(&path).await else {
+        continue;
+      };
+
+      if entry.source.url.is_empty() {
+        bare.push((path, entry));
+        continue;
+      }
+
+      if path != self.cache_path(&entry.source.url) {
+        self.move_cache_file(&path, &entry).await?;
+      }
+      cached.push(entry);
+    }
+
+    for (path, mut entry) in bare {
+      let cached_urls: Vec<String> = cached.iter().map(|e| e.source.url.clone()).collect();
+      if let Some(url) = legacy_source_url(&entry.repository.repo_id, &cached_urls) {
+        tracing::info!(repo_id = %entry.repository.repo_id, %url, "Matched legacy cache file");
+        if let Some(base) = repository_base_url(&url) {
+          resolve_relative_urls(&mut entry.repository, &base);
+        }
+        entry.source.url = url;
+        self.move_cache_file(&path, &entry).await?;
+      }
+      cached.push(entry);
+    }
+
+    Ok(cached)
+  }
+
+  /// Write an entry to the file for its URL and remove the file it was read from
+  async fn move_cache_file(&self, from: &Path, entry: &CachedRepository) -> Result<()> {
+    self.cache_repository(entry).await?;
+    async_fs::remove_file(from)
+      .await
+      .map_err(|e| SilkSpoolError::io("remove cache file", from, e))
+  }
+
+  /// Find the cached repository that was last fetched from `url`
+  ///
+  /// When a key is pinned for the repository, copies that were not verified with it are ignored.
+  async fn find_cached_by_url(&self, url: &str) -> Option<CachedRepository> {
+    let pinned_key = pinned_key(url);
+    self.read_cache_dir().await.ok()?.into_iter().find(|entry| {
+      entry.source.url == url && (pinned_key.is_none() || entry.source.signed_with == pinned_key)
+    })
+  }
+
+  /// Load the cached copy of the repository at `url`
+  pub async fn load_cached_repository(&self, url: &str) -> Result<ModRepository> {
+    self
+      .find_cached_by_url(url)
+      .await
+      .map(|entry| entry.repository)
+      .ok_or_else(|| SilkSpoolError::invalid_input(format!("Repository {} is not cached", url)))
+  }
+
+  /// Get all cached repositories
+  pub async fn get_cached_repositories(&self) -> Result<Vec<RepositoryInfo>> {
+    Ok(
+      self
+        .read_cache_dir()
+        .await?
+        .into_iter()
+        .map(|CachedRepository { source, repository }| RepositoryInfo {
+          id: repository.repo_id.clone(),
+          name: repository.name.clone(),
+          url: source.url,
+          version: repository.version,
+          last_updated: Some(source.fetched_at).filter(|at| !at.is_empty()),
+          mod_count: repository.mods.len(),
+        })
+        .collect(),
+    )
+  }
+
+  /// Clear the cached copy of the repository at `url`
+  pub async fn clear_repository_cache(&self, url: &str) -> Result<()> {
+    // Move a file still named after the repository ID first, so it is found
+    self.read_cache_dir().await?;
+    let cache_path = self.cache_path(url);
+
+    if cache_path.exists() {
+      async_fs::remove_file(&cache_path)
+        .await
+        .map_err(|e| SilkSpoolError::io("remove cache file", &cache_path, e))?;
+    }
+
+    Ok(())
+  }
+
+  /// Clear all cached repositories
+  pub async fn clear_all_cache(&self) -> Result<()> {
+    if Path::new(&self.cache_dir).exists() {
+      async_fs::remove_dir_all(&self.cache_dir)
+        .await
+        .map_err(|e| SilkSpoolError::io("remove cache directory", &self.cache_dir, e))?;
+    }
+
+    Ok(())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::{
+    config::{add_repo, test_dirs, update_repo, RepoEntry},
+    schema::SCHEMA_VERSION,
+  };
+
+  fn repository(repo_id: &str) -> ModRepository {
+    ModRepository {
+      schema_version: SCHEMA_VERSION,
+      repo_id: repo_id.to_string(),
+      name: repo_id.to_string(),
+      version: 1,
+      mods: vec![],
+      extra: Default::default(),
+    }
+  }
+
+  #[tokio::test]
+  async fn test_cached_repository_keeps_its_source() {
+    let cache = tempfile::tempdir().unwrap();
+    let manager = RepositoryManager::new(cache.path().to_string_lossy().to_string());
+    let entry = CachedRepository {
+      source: FetchMetadata {
+        url: "https://example.com/mods.json".to_string(),
+        etag: Some("\"abc\"".to_string()),
+        last_modified: None,
+        fetched_at: "2024-01-01T00:00:00Z".to_string(),
+        signed_with: None,
+      },
+      repository: repository("main"),
+    };
+
+    manager.cache_repository(&entry).await.unwrap();
+
+    let found = manager
+      .find_cached_by_url("https://example.com/mods.json")
+      .await
+      .unwrap();
+    assert_eq!(found.source.etag.as_deref(), Some("\"abc\""));
+    assert!(manager
+      .find_cached_by_url("https://example.com/other.json")
+      .await
+      .is_none());
+
+    let cached = manager.get_cached_repositories().await.unwrap();
+    assert_eq!(cached.len(), 1);
+    assert_eq!(cached[0].url, "https://example.com/mods.json");
+    assert_eq!(
+      cached[0].last_updated.as_deref(),
+      Some("2024-01-01T00:00:00Z")
+    );
+  }
+
+  #[tokio::test]
+  async fn test_get_repository_falls_back_to_stale_cache() {
+    let _dirs = test_dirs::isolate();
+    let cache = tempfile::tempdir().unwrap();
+    let manager = RepositoryManager::new(cache.path().to_string_lossy().to_string());
+    // Nothing listens on the discard port, so the fetch fails quickly
+    let url = "http://127.0.0.1:9/mods.json";
+    let entry = CachedRepository {
+      source: FetchMetadata {
+        url: url.to_string(),
+        fetched_at: "2024-01-01T00:00:00Z".to_string(),
+        ..Default::default()
+      },
+      repository: repository("main"),
+    };
+    manager.cache_repository(&entry).await.unwrap();
+
+    let result = manager.get_repository(url).await.unwrap();
+    assert!(result.stale);
+    assert!(result.error.is_some());
+    assert_eq!(result.repository.repo_id, "main");
+    assert_eq!(result.fetched_at.as_deref(), Some("2024-01-01T00:00:00Z"));
+
+    assert!(manager
+      .get_repository("http://127.0.0.1:9/other.json")
+      .await
+      .is_err());
+  }
+
+  #[tokio::test]
+  async fn test_local_repositories_resolve_relative_downloads() {
+    let _dirs = test_dirs::isolate();
+    let cache = tempfile::tempdir().unwrap();
+    let manager = RepositoryManager::new(cache.path().to_string_lossy().to_string());
+    let local = tempfile::tempdir().unwrap();
+    let repo_json = r#"{
+      "repo_id": "local",
+      "name": "Local",
+      "version": 1,
+      "mods": [{
+        "id": "a",
+        "title": "A",
+        "version": "1.0.0",
+        "description": "Test mod",
+        "requirements": [],
+        "images": [],
+        "downloads": [
+          {"label": "Relative", "url": "files/a.zip"},
+          {"label": "Absolute", "url": "https://example.com/a.zip"}
+        ],
+        "homepage": null,
+        "authors": [],
+        "game_version": "1.0",
+        "updated_at": "2024-01-01"
+      }]
+    }"#;
+    std::fs::write(local.path().join("mods.json"), repo_json).unwrap();
+
+    let location = local.path().to_string_lossy().to_string();
+    let repository = manager.fetch_repository(&location).await.unwrap();
+
+    let downloads = &repository.mods[0].downloads;
+    let expected =
+      reqwest::Url::from_file_path(local.path().canonicalize().unwrap().join("files/a.zip"))
+        .unwrap();
+    assert_eq!(downloads[0].url, expected.to_string());
+    assert_eq!(downloads[1].url, "https://example.com/a.zip");
+    assert!(manager.find_cached_by_url(&location).await.is_some());
+
+    let file_url = reqwest::Url::from_file_path(local.path().join("mods.json")).unwrap();
+    assert!(manager.fetch_repository(file_url.as_str()).await.is_ok());
+  }
+
+  #[tokio::test]
+  async fn test_repositories_with_a_pinned_key_must_be_signed() {
+    const KEY: &str = "RWQBAgMEBQYHCAOhB7/zzhC+HXDdGOdLwJln5NYwm6UNXx3chmQSVTG4";
+    const OTHER_KEY: &str = "RWQJCgsMDQ4PECmsuuFBvMrwsi4alNNNC8c2HlJtC/4SyJeUvJMilm3X";
+    const DOCUMENT: &str = r#"{"repo_id": "signed", "name": "Signed", "version": 1, "mods": []}"#;
+    const SIGNATURE: &str = "untrusted comment: signature from minisign secret key
+RUQBAgMEBQYHCCMBFKpcELe0LoTlz9zdytmaCcJJLfbQtux4ZRNmC33lkGel7Pg5gwtS21p8PbfHijEicJu5b1QZR8LMYI0TrAo=
+trusted comment: timestamp:1700000000
+iFJylEIryscZ//ONfZXZuno9pz2M7LSp5dCDsvdorFy6woPsaGZC5P9kaoqNr//XF2gpGH1NOtLIEgK8C3w5Dw==
+";
+
+    let _dirs = test_dirs::isolate();
+    let cache = tempfile::tempdir().unwrap();
+    let manager = RepositoryManager::new(cache.path().to_string_lossy().to_string());
+    let local = tempfile::tempdir().unwrap();
+    let location = local.path().to_string_lossy().to_string();
+    let document = local.path().join("mods.json");
+    let signature = local.path().join("mods.json.minisig");
+    std::fs::write(&document, DOCUMENT).unwrap();
+    add_repo(RepoEntry {
+      pinned_key: Some(KEY.to_string()),
+      ..RepoEntry::new(location.clone())
+    })
+    .unwrap();
+
+    let error = manager.fetch_repository(&location).await.unwrap_err();
+    assert_eq!(error.code(), "signature_mismatch");
+
+    std::fs::write(&signature, SIGNATURE).unwrap();
+    let repository = manager.fetch_repository(&location).await.unwrap();
+    assert_eq!(repository.repo_id, "signed");
+    assert!(manager.load_cached_repository(&location).await.is_ok());
+
+    std::fs::write(&document, DOCUMENT.replace("Signed", "Tampered")).unwrap();
+    let error = manager.fetch_repository(&location).await.unwrap_err();
+    assert_eq!(error.code(), "signature_mismatch");
+
+    // A copy verified with a key that is no longer pinned is not trusted either
+    std::fs::write(&document, DOCUMENT).unwrap();
+    update_repo(RepoEntry {
+      pinned_key: Some(OTHER_KEY.to_string()),
+      ..RepoEntry::new(location.clone())
+    })
+    .unwrap();
+    let error = manager.fetch_repository(&location).await.unwrap_err();
+    assert_eq!(error.code(), "signature_mismatch");
+    assert!(manager.load_cached_repository(&location).await.is_err());
+  }
+
+  #[tokio::test]
+  async fn test_cached_repositories_resolve_relative_urls() {
+    let cache = tempfile::tempdir().unwrap();
+    let manager = RepositoryManager::new(cache.path().to_string_lossy().to_string());
+    let mut repo = repository("remote");
+    repo.mods = serde_json::from_str(
+      r#"[{
+        "id": "a",
+        "title": "A",
+        "version": "1.0.0",
+        "description": "Test mod",
+        "requirements": [],
+        "images": ["images/a.png", "https://cdn.example.com/a.png"],
+        "downloads": [{"label": "Download", "url": "../files/a.zip"}],
+        "homepage": null,
+        "authors": [],
+        "game_version": "1.0",
+        "updated_at": "2024-01-01",
+        "versions": [{"version": "0.9.0", "downloads": [{"label": "Old", "url": "a-0.9.zip"}]}]
+      }]"#,
+    )
+    .unwrap();
+    let entry = CachedRepository {
+      source: FetchMetadata {
+        url: "https://example.com/repo/mods.json".to_string(),
+        ..Default::default()
+      },
+      repository: repo,
+    };
+    manager.cache_repository(&entry).await.unwrap();
+
+    let mod_item = &manager
+      .load_cached_repository("https://example.com/repo/mods.json")
+      .await
+      .unwrap()
+      .mods[0];
+    assert_eq!(mod_item.downloads[0].url, "https://example.com/files/a.zip");
+    assert_eq!(
+      mod_item.versions[0].downloads[0].url,
+      "https://example.com/repo/a-0.9.zip"
+    );
+    assert_eq!(mod_item.images[0], "https://example.com/repo/images/a.png");
+    assert_eq!(mod_item.images[1], "https://cdn.example.com/a.png");
+  }
+
+  #[test]
+  fn test_validate_repo_id() {
+    assert!(validate_repo_id("silk-spool.mods_2").is_ok());
+    assert!(validate_repo_id("").is_err());
+    assert!(validate_repo_id("..").is_err());
+    assert!(validate_repo_id("../../etc/passwd").is_err());
+    assert!(validate_repo_id("nested/repo").is_err());
+    assert!(validate_repo_id("C:\\repo").is_err());
+  }
+
+  #[tokio::test]
+  async fn test_cache_paths_stay_in_the_cache() {
+    let cache = tempfile::tempdir().unwrap();
+    let cache_dir = cache.path().join("repos");
+    let manager = RepositoryManager::new(cache_dir.to_string_lossy().to_string());
+    std::fs::write(
+      cache.path().join("repo_.json"),
+      serde_json::to_string(&repository("outside")).unwrap(),
+    )
+    .unwrap();
+
+    assert_eq!(
+      manager.cache_path("../").parent(),
+      Some(cache_dir.as_path())
+    );
+    assert!(manager.load_cached_repository("../").await.is_err());
+    manager.clear_repository_cache("../").await.unwrap();
+    assert!(cache.path().join("repo_.json").exists());
+
+    let mut escaping = repository("ok");
+    escaping.repo_id = "../escape".to_string();
+    assert!(!validate_repository(&escaping, None).is_valid());
+  }
+
+  #[tokio::test]
+  async fn test_repositories_sharing_an_id_are_cached_apart() {
+    let cache = tempfile::tempdir().unwrap();
+    let manager = RepositoryManager::new(cache.path().to_string_lossy().to_string());
+    for (url, etag) in [
+      ("https://one.example.com/mods.json", "\"one\""),
+      ("https://two.example.com/mods.json", "\"two\""),
+    ] {
+      let entry = CachedRepository {
+        source: FetchMetadata {
+          url: url.to_string(),
+          etag: Some(etag.to_string()),
+          ..Default::default()
+        },
+        repository: repository("main"),
+      };
+      manager.cache_repository(&entry).await.unwrap();
+    }
+
+    assert_eq!(manager.get_cached_repositories().await.unwrap().len(), 2);
+    let found = manager
+      .find_cached_by_url("https://two.example.com/mods.json")
+      .await
+      .unwrap();
+    assert_eq!(found.source.etag.as_deref(), Some("\"two\""));
+
+    manager
+      .clear_repository_cache("https://one.example.com/mods.json")
+      .await
+      .unwrap();
+    let cached = manager.get_cached_repositories().await.unwrap();
+    assert_eq!(cached.len(), 1);
+    assert_eq!(cached[0].url, "https://two.example.com/mods.json");
+  }
+
+  #[tokio::test]
+  async fn test_cache_files_named_after_the_id_are_moved() {
+    let cache = tempfile::tempdir().unwrap();
+    let manager = RepositoryManager::new(cache.path().to_string_lossy().to_string());
+    let legacy_path = cache.path().join("repo_main.json");
+    let entry = CachedRepository {
+      source: FetchMetadata {
+        url: "https://example.com/mods.json".to_string(),
+        ..Default::default()
+      },
+      repository: repository("main"),
+    };
+    manager
+      .write_cache_file(&legacy_path, &entry)
+      .await
+      .unwrap();
+
+    assert!(manager
+      .find_cached_by_url("https://example.com/mods.json")
+      .await
+      .is_some());
+    assert!(!legacy_path.exists());
+    assert!(manager.cache_path("https://example.com/mods.json").exists());
+  }
+
+  #[tokio::test]
+  async fn test_bare_cache_files_are_migrated() {
+    let _dirs = test_dirs::isolate();
+    let cache = tempfile::tempdir().unwrap();
+    let manager = RepositoryManager::new(cache.path().to_string_lossy().to_string());
+    let legacy_path = cache.path().join("repo_old.json");
+    std::fs::write(
+      &legacy_path,
+      serde_json::to_string(&repository("old")).unwrap(),
+    )
+    .unwrap();
+
+    let cached = manager.get_cached_repositories().await.unwrap();
+    assert_eq!(cached.len(), 1);
+    assert_eq!(cached[0].id, "old");
+    assert_eq!(cached[0].url, "");
+    assert!(cached[0].last_updated.is_some());
+
+    let rewritten: serde_json::Value =
+      serde_json::from_str(&std::fs::read_to_string(&legacy_path).unwrap()).unwrap();
+    assert_eq!(rewritten["repository"]["repo_id"], "old");
+  }
+}
@@ -0,0 +1,958 @@
+use std::path::Path;
+
+use semver::{Version, VersionReq};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+  catalog::Catalog,
+  conflicts::ConflictPolicy,
+  error::{Result, SilkSpoolError},
+  installed_mods::get_installed_mod,
+  installer::install_tracked_version,
+  schema::{Dependency, Mod, ModRepository},
+  types::{InstalledMod, ModActionFailure},
+};
+
+/// One mod to install as part of a plan
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PlannedInstall {
+  pub mod_id: String,
+  pub title: String,
+  pub version: String,
+  pub repo_id: String,
+  pub download_url: String,
+}
+
+/// Mods to install in order, dependencies first
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct InstallPlan {
+  pub steps: Vec<PlannedInstall>,
+  /// Dependencies that are already installed in a suitable version
+  pub satisfied: Vec<String>,
+  /// Optional dependencies that are not installed and are left out of the plan
+  pub optional: Vec<String>,
+  /// Declared incompatibilities the plan would introduce
+  pub incompatibilities: Vec<Incompatibility>,
+}
+
+/// Two mods that the repository declares cannot be used together
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Incompatibility {
+  pub mod_id: String,
+  pub conflicts_with: String,
+  /// The conflicting version range, when the conflict is limited to some versions
+  pub version: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct InstallPlanResult {
+  pub installed: Vec<String>,
+  pub failed: Vec<ModActionFailure>,
+  /// Steps that were not attempted because an earlier one failed
+  pub skipped: Vec<String>,
+}
+
+/// Parse a version string, tolerating a leading `v`
+pub fn parse_version(version: &str) -> Option<Version> {
+  Version::parse(version.trim().trim_start_matches('v')).ok()
+}
+
+/// Get the version a repository offers for a mod
+pub fn mod_version(mod_item: &Mod) -> Option<Version> {
+  parse_version(&mod_item.version)
+}
+
+/// Pick the newest release of a mod that fits a range, preferring the current one
+fn matching_release<'m>(mod_item: &'m Mod, range: Option<&str>) -> Result<Option<&'m str>> {
+  if satisfies(mod_version(mod_item).as_ref(), range)? {
+    return Ok(Some(&mod_item.version));
+  }
+
+  let mut best: Option<(Version, &str)> = None;
+  for release in &mod_item.versions {
+    let Some(version) = parse_version(&release.version) else {
+      continue;
+    };
+    if satisfies(Some(&version), range)? && best.as_ref().is_none_or(|(b, _)| version > *b) {
+      best = Some((version, &release.version));
+    }
+  }
+
+  Ok(best.map(|(_, version)| version))
+}
+
+/// Check a version against an optional semver range, accepting versions that are not known
+fn satisfies(version: Option<&Version>, range: Option<&str>) -> Result<bool> {
+  let Some(range) = range else {
+    return Ok(true);
+  };
+
+  let requirement = VersionReq::parse(range).map_err(|e| SilkSpoolError::Dependency {
+    mod_id: None,
+    message: format!("Invalid version range '{}': {}", range, e),
+  })?;
+
+  Ok(version.map(|v| requirement.matches(v)).unwrap_or(true))
+}
+
+/// A version range one mod asked for, checked against the plan once it is complete
+struct VersionRequest {
+  mod_id: String,
+  range: String,
+  required_by: Option<String>,
+}
+
+/// Resolves mod dependencies across every cached repository
+pub struct Resolver<'a> {
+  repositories: &'a [ModRepository],
+  installed: &'a [InstalledMod],
+}
+
+impl<'a> Resolver<'a> {
+  pub fn new(repositories: &'a [ModRepository], installed: &'a [InstalledMod]) -> Self {
+    Self {
+      repositories,
+      installed,
+    }
+  }
+
+  /// Find a mod, preferring the repository named by `repo_hint`
+  fn find_mod(
+    &self,
+    mod_id: &str,
+    repo_hint: Option<&str>,
+  ) -> Option<(&'a ModRepository, &'a Mod)> {
+    let lookup =
+      |repo: &'a ModRepository| repo.mods.iter().find(|m| m.id == mod_id).map(|m| (repo, m));
+
+    repo_hint
+      .and_then(|hint| {
+        self
+          .repositories
+          .iter()
+          .filter(|repo| repo.repo_id == hint)
+          .find_map(lookup)
+      })
+      .or_else(|| self.repositories.iter().find_map(lookup))
+  }
+
+  /// Find a mod that declares it provides `mod_id`
+  fn find_provider(&self, mod_id: &str) -> Option<(&'a ModRepository, &'a Mod)> {
+    self.repositories.iter().find_map(|repo| {
+      repo
+        .mods
+        .iter()
+        .find(|m| m.provides.iter().any(|p| p == mod_id))
+        .map(|m| (repo, m))
+    })
+  }
+
+  /// Check whether a mod is, or provides, `mod_id`
+  fn is_or_provides(&self, candidate_id: &str, mod_id: &str) -> bool {
+    candidate_id == mod_id
+      || self
+        .find_mod(candidate_id, None)
+        .map(|(_, definition)| definition.provides.iter().any(|p| p == mod_id))
+        .unwrap_or(false)
+  }
+
+  /// Build the plan to install a mod together with everything it depends on
+  pub fn plan_install(&self, mod_id: &str) -> Result<InstallPlan> {
+    let target = Dependency {
+      id: mod_id.to_string(),
+      version: None,
+      optional: false,
+      repo: None,
+    };
+
+    self.plan_targets(&[target])
+  }
+
+  /// Build a single plan that installs several mods, each within its given version range
+  pub fn plan_targets(&self, targets: &[Dependency]) -> Result<InstallPlan> {
+    let mut plan = InstallPlan::default();
+    let mut missing = Vec::new();
+    let mut requests = Vec::new();
+    for target in targets {
+      self.visit(
+        target,
+        None,
+        &mut Vec::new(),
+        &mut plan,
+        &mut missing,
+        &mut requests,
+      )?;
+    }
+
+    if !missing.is_empty() {
+      return Err(SilkSpoolError::Dependency {
+        mod_id: None,
+        message: format!("Missing dependencies: {}", missing.join(", ")),
+      });
+    }
+
+    // A mod is planned once, so every range asked for it must accept the version chosen
+    let mut clashes = Vec::new();
+    for request in &requests {
+      let Some((chosen_id, chosen_version)) = self.chosen_version(&plan, &request.mod_id) else {
+        continue;
+      };
+      if !satisfies(
+        parse_version(&chosen_version).as_ref(),
+        Some(&request.range),
+      )? {
+        clashes.push(format!(
+          "{} needs {} {}, but the plan uses {} {}",
+          request.required_by.as_deref().unwrap_or("the request"),
+          request.mod_id,
+          request.range,
+          chosen_id,
+          chosen_version
+        ));
+      }
+    }
+
+    if !clashes.is_empty() {
+      return Err(SilkSpoolError::Dependency {
+        mod_id: None,
+        message: format!("Conflicting version requirements: {}", clashes.join("; ")),
+      });
+    }
+
+    // Check the planned mods against each other and against what stays installed
+    let mut resulting_set: Vec<(String, String)> = self
+      .installed
+      .iter()
+      .filter(|m| !plan.steps.iter().any(|step| step.mod_id == m.mod_id))
+      .map(|m| (m.mod_id.clone(), m.version.clone()))
+      .collect();
+    resulting_set.extend(
+      plan
+        .steps
+        .iter()
+        .map(|step| (step.mod_id.clone(), step.version.clone())),
+    );
+
+    plan.incompatibilities = self
+      .find_incompatibilities(&resulting_set)
+      .into_iter()
+      .filter(|incompatibility| {
+        plan.steps.iter().any(|step| {
+          step.mod_id == incompatibility.mod_id || step.mod_id == incompatibility.conflicts_with
+        })
+      })
+      .collect();
+
+    Ok(plan)
+  }
+
+  /// Find the declared incompatibilities within a set of `(mod ID, version)` pairs
+  pub fn find_incompatibilities(&self, mods: &[(String, String)]) -> Vec<Incompatibility> {
+    let mut found = Vec::new();
+
+    for (mod_id, _) in mods {
+      let Some((_, definition)) = self.find_mod(mod_id, None) else {
+        continue;
+      };
+
+      for conflict in &definition.conflicts {
+        for (other_id, other_version) in mods {
+          if other_id == mod_id || !self.is_or_provides(other_id, &conflict.id) {
+            continue;
+          }
+
+          // An unreadable range is treated as covering every version
+          let other_version = parse_version(other_version);
+          if satisfies(other_version.as_ref(), conflict.version.as_deref()).unwrap_or(true) {
+            found.push(Incompatibility {
+              mod_id: mod_id.clone(),
+              conflicts_with: other_id.clone(),
+              version: conflict.version.clone(),
+            });
+          }
+        }
+      }
+    }
+
+    found
+  }
+
+  /// Find the declared incompatibilities installing one mod next to the installed ones would cause
+  pub fn incompatibilities_with(&self, mod_id: &str, version: &str) -> Vec<Incompatibility> {
+    let mut resulting_set: Vec<(String, String)> = self
+      .installed
+      .iter()
+      .filter(|m| m.mod_id != mod_id)
+      .map(|m| (m.mod_id.clone(), m.version.clone()))
+      .collect();
+    resulting_set.push((mod_id.to_string(), version.to_string()));
+
+    self
+      .find_incompatibilities(&resulting_set)
+      .into_iter()
+      .filter(|i| i.mod_id == mod_id || i.conflicts_with == mod_id)
+      .collect()
+  }
+
+  /// Find the declared incompatibilities among the installed mods
+  pub fn installed_incompatibilities(&self) -> Vec<Incompatibility> {
+    let installed: Vec<(String, String)> = self
+      .installed
+      .iter()
+      .map(|m| (m.mod_id.clone(), m.version.clone()))
+      .collect();
+
+    self.find_incompatibilities(&installed)
+  }
+
+  /// Find the installed mod that stands in for `mod_id`, preferring the mod itself over providers
+  fn installed_match(&self, mod_id: &str) -> Option<&'a InstalledMod> {
+    self
+      .installed
+      .iter()
+      .find(|m| m.mod_id == mod_id)
+      .or_else(|| {
+        self
+          .installed
+          .iter()
+          .find(|m| self.is_or_provides(&m.mod_id, mod_id))
+      })
+  }
+
+  /// Find the planned step that stands in for `mod_id`, preferring the mod itself over providers
+  fn planned_match<'p>(&self, plan: &'p InstallPlan, mod_id: &str) -> Option<&'p PlannedInstall> {
+    plan
+      .steps
+      .iter()
+      .find(|step| step.mod_id == mod_id)
+      .or_else(|| {
+        plan
+          .steps
+          .iter()
+          .find(|step| self.is_or_provides(&step.mod_id, mod_id))
+      })
+  }
+
+  /// Find the mod and version a plan settles on for `mod_id`, planned or already installed
+  fn chosen_version(&self, plan: &InstallPlan, mod_id: &str) -> Option<(String, String)> {
+    if let Some(step) = self.planned_match(plan, mod_id) {
+      return Some((step.mod_id.clone(), step.version.clone()));
+    }
+
+    if !plan.satisfied.iter().any(|id| id == mod_id) {
+      return None;
+    }
+
+    self
+      .installed_match(mod_id)
+      .map(|m| (m.mod_id.clone(), m.version.clone()))
+  }
+
+  fn visit(
+    &self,
+    dependency: &Dependency,
+    required_by: Option<&str>,
+    stack: &mut Vec<String>,
+    plan: &mut InstallPlan,
+    missing: &mut Vec<String>,
+    requests: &mut Vec<VersionRequest>,
+  ) -> Result<()> {
+    if let Some(position) = stack.iter().position(|id| *id == dependency.id) {
+      let mut cycle = stack[position..].to_vec();
+      cycle.push(dependency.id.clone());
+      return Err(SilkSpoolError::Dependency {
+        mod_id: Some(dependency.id.clone()),
+        message: format!("Dependency cycle detected: {}", cycle.join(" -> ")),
+      });
+    }
+
+    if let Some(range) = &dependency.version {
+      requests.push(VersionRequest {
+        mod_id: dependency.id.clone(),
+        range: range.clone(),
+        required_by: required_by.map(str::to_string),
+      });
+    }
+
+    // A planned provider covers the ID it provides as well as its own
+    if self.planned_match(plan, &dependency.id).is_some()
+      || plan.satisfied.contains(&dependency.id)
+      || plan.optional.contains(&dependency.id)
+    {
+      return Ok(());
+    }
+
+    // Dependencies that are already installed in a suitable version need no work
+    if required_by.is_some() {
+      if let Some(installed) = self.installed_match(&dependency.id) {
+        let installed_version = parse_version(&installed.version);
+        if satisfies(installed_version.as_ref(), dependency.version.as_deref())? {
+          plan.satisfied.push(dependency.id.clone());
+          return Ok(());
+        }
+      }
+    }
+
+    // Optional dependencies are never installed automatically
+    if dependency.optional {
+      plan.optional.push(dependency.id.clone());
+      return Ok(());
+    }
+
+    let describe = || match required_by {
+      Some(parent) => format!("{} (required by {})", dependency.id, parent),
+      None => dependency.id.clone(),
+    };
+
+    let Some((repo, candidate)) = self
+      .find_mod(&dependency.id, dependency.repo.as_deref())
+      .or_else(|| self.find_provider(&dependency.id))
+    else {
+      missing.push(describe());
+      return Ok(());
+    };
+
+    let Some(version) = matching_release(candidate, dependency.version.as_deref())? else {
+      missing.push(format!(
+        "{} {}",
+        describe(),
+        dependency.version.as_deref().unwrap_or_default()
+      ));
+      return Ok(());
+    };
+
+    let download = candidate
+      .downloads_for_version(version)
+      .and_then(|downloads| downloads.first())
+      .ok_or_else(|| SilkSpoolError::DownloadUnavailable {
+        mod_id: candidate.id.clone(),
+        message: format!("Mod '{}' {} has no downloads", candidate.id, version),
+      })?;
+
+    stack.push(dependency.id.clone());
+    for child in &candidate.dependencies {
+      self.visit(child, Some(&candidate.id), stack, plan, missing, requests)?;
+    }
+    stack.pop();
+
+    plan.steps.push(PlannedInstall {
+      mod_id: candidate.id.clone(),
+      title: candidate.title.clone(),
+      version: version.to_string(),
+      repo_id: repo.repo_id.clone(),
+      download_url: download.url.clone(),
+    });
+
+    Ok(())
+  }
+
+  /// Check whether an installed mod other than `mod_id` still satisfies `dependency`
+  fn satisfied_without(&self, dependency: &Dependency, mod_id: &str) -> bool {
+    self.installed.iter().any(|other| {
+      other.mod_id != mod_id
+        && self.is_or_provides(&other.mod_id, &dependency.id)
+        && satisfies(
+          parse_version(&other.version).as_ref(),
+          dependency.version.as_deref(),
+        )
+        .unwrap_or(false)
+    })
+  }
+
+  /// List the installed mods that require `mod_id`
+  ///
+  /// A dependency that another installed mod still provides does not hold `mod_id` in place.
+  pub fn dependents_of(&self, mod_id: &str) -> Vec<String> {
+    self
+      .installed
+      .iter()
+      .filter(|installed| installed.mod_id != mod_id)
+      .filter(|installed| {
+        self
+          .find_mod(&installed.mod_id, None)
+          .map(|(_, definition)| {
+            definition.dependencies.iter().any(|d| {
+              !d.optional
+                && self.is_or_provides(mod_id, &d.id)
+                && !self.satisfied_without(d, mod_id)
+            })
+          })
+          .unwrap_or(false)
+      })
+      .map(|installed| installed.mod_id.clone())
+      .collect()
+  }
+}
+
+/// Summarize incompatibilities in an error for the user
+pub fn describe_incompatibilities(incompatibilities: &[Incompatibility]) -> SilkSpoolError {
+  let pairs: Vec<String> = incompatibilities
+    .iter()
+    .map(|i| format!("{} conflicts with {}", i.mod_id, i.conflicts_with))
+    .collect();
+
+  SilkSpoolError::Incompatible {
+    message: format!("Incompatible mods: {}", pairs.join(", ")),
+  }
+}
+
+/// Install every step of a plan in order, stopping at the first failure
+///
+/// Installed mods are upgraded in place, keeping the replaced version for rollback. Steps that
+/// succeeded before a failure stay installed, since each is a complete mod on its own; the failed
+/// step is left as it was, and the steps after it are reported as skipped. Downloads are read
+/// from the repository `catalog` lists each mod in.
+#[tracing::instrument(skip_all)]
+pub async fn execute_install_plan(
+  plan: &InstallPlan,
+  game_path: &Path,
+  catalog: &Catalog,
+) -> InstallPlanResult {
+  let mut result = InstallPlanResult::default();
+  tracing::info!(steps = plan.steps.len(), "Installing plan");
+
+  for (index, step) in plan.steps.iter().enumerate() {
+    let enabled = match get_installed_mod(&step.mod_id, game_path) {
+      Ok(installed) => installed.is_none_or(|m| m.enabled),
+      Err(_) => true,
+    };
+    let outcome = install_tracked_version(
+      &step.mod_id,
+      &step.title,
+      &step.version,
+      &catalog.download_source(&step.mod_id, &step.download_url),
+      game_path,
+      enabled,
+      ConflictPolicy::Abort,
+    )
+    .await;
+
+    match outcome {
+      Ok(()) => result.installed.push(step.mod_id.clone()),
+      Err(error) => {
+        // Later steps may depend on this one, so stop here
+        tracing::warn!(mod_id = %step.mod_id, %error, "Install plan stopped");
+        result.failed.push(ModActionFailure {
+          mod_id: step.mod_id.clone(),
+          error,
+        });
+        result.skipped = plan.steps[index + 1..]
+          .iter()
+          .map(|step| step.mod_id.clone())
+          .collect();
+        break;
+      }
+    }
+  }
+
+  result
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::{
+    config::test_dirs,
+    schema::{Download, ModConflict, ModVersion, SCHEMA_VERSION},
+  };
+
+  fn repo_mod(id: &str, version: &str, dependencies: Vec<Dependency>) -> Mod {
+    Mod {
+      id: id.to_string(),
+      title: id.to_string(),
+      version: version.to_string(),
+      description: "A test mod".to_string(),
+      requirements: vec![],
+      images: vec![],
+      downloads: vec![Download {
+        label: "Download".to_string(),
+        url: format!("https://example.com/{}.zip", id),
+      }],
+      homepage: None,
+      authors: vec![],
+      game_version: "1.0".to_string(),
+      updated_at: "2024-01-01T00:00:00Z".to_string(),
+      dependencies,
+      conflicts: vec![],
+      provides: vec![],
+      versions: vec![],
+      extra: Default::default(),
+    }
+  }
+
+  fn conflict(id: &str, version: Option<&str>) -> ModConflict {
+    ModConflict {
+      id: id.to_string(),
+      version: version.map(|v| v.to_string()),
+    }
+  }
+
+  fn dependency(id: &str, version: Option<&str>, optional: bool) -> Dependency {
+    Dependency {
+      id: id.to_string(),
+      version: version.map(|v| v.to_string()),
+      optional,
+      repo: None,
+    }
+  }
+
+  fn repository(mods: Vec<Mod>) -> ModRepository {
+    ModRepository {
+      schema_version: SCHEMA_VERSION,
+      repo_id: "test".to_string(),
+      name: "Test".to_string(),
+      version: 1,
+      mods,
+      extra: Default::default(),
+    }
+  }
+
+  fn installed(mod_id: &str, version: &str) -> InstalledMod {
+    InstalledMod {
+      mod_id: mod_id.to_string(),
+      mod_title: mod_id.to_string(),
+      version: version.to_string(),
+      installed_at: "2024-01-01T00:00:00Z".to_string(),
+      installed_files: vec![],
+      game_path: "/game".to_string(),
+      download_url: None,
+      enabled: true,
+      pinned_version: None,
+      history: vec![],
+    }
+  }
+
+  #[test]
+  fn test_plan_installs_dependencies_first() {
+    let repos = vec![repository(vec![
+      repo_mod(
+        "app",
+        "1.0.0",
+        vec![dependency("lib", Some(">=1.0"), false)],
+      ),
+      repo_mod("lib", "1.2.0", vec![dependency("core", None, false)]),
+      repo_mod("core", "0.1.0", vec![dependency("extra", None, true)]),
+    ])];
+
+    let plan = Resolver::new(&repos, &[]).plan_install("app").unwrap();
+
+    let order: Vec<&str> = plan.steps.iter().map(|s| s.mod_id.as_str()).collect();
+    assert_eq!(order, vec!["core", "lib", "app"]);
+    assert_eq!(plan.optional, vec!["extra".to_string()]);
+  }
+
+  #[test]
+  fn test_plan_skips_satisfied_dependencies() {
+    let repos = vec![repository(vec![
+      repo_mod("app", "1.0.0", vec![dependency("lib", Some("^1"), false)]),
+      repo_mod("lib", "1.2.0", vec![]),
+    ])];
+    let installed = vec![installed("lib", "1.0.0")];
+
+    let plan = Resolver::new(&repos, &installed)
+      .plan_install("app")
+      .unwrap();
+
+    assert_eq!(plan.steps.len(), 1);
+    assert_eq!(plan.satisfied, vec!["lib".to_string()]);
+  }
+
+  #[test]
+  fn test_plan_falls_back_to_older_release() {
+    let mut lib = repo_mod("lib", "2.0.0", vec![]);
+    lib.versions.push(ModVersion {
+      version: "1.4.0".to_string(),
+      downloads: vec![Download {
+        label: "Download".to_string(),
+        url: "https://example.com/lib-1.4.0.zip".to_string(),
+      }],
+      game_version: None,
+      updated_at: None,
+    });
+    let repos = vec![repository(vec![
+      repo_mod("app", "1.0.0", vec![dependency("lib", Some("^1"), false)]),
+      lib,
+    ])];
+
+    let plan = Resolver::new(&repos, &[]).plan_install("app").unwrap();
+
+    assert_eq!(plan.steps[0].version, "1.4.0");
+    assert_eq!(
+      plan.steps[0].download_url,
+      "https://example.com/lib-1.4.0.zip"
+    );
+  }
+
+  #[test]
+  fn test_plan_detects_cycles() {
+    let repos = vec![repository(vec![
+      repo_mod("a", "1.0.0", vec![dependency("b", None, false)]),
+      repo_mod("b", "1.0.0", vec![dependency("a", None, false)]),
+    ])];
+
+    let error = Resolver::new(&repos, &[]).plan_install("a").unwrap_err();
+    assert_eq!(error.code(), "dependency");
+    assert!(error.to_string().contains("a -> b -> a"));
+  }
+
+  #[test]
+  fn test_plan_reports_missing_dependencies() {
+    let repos = vec![repository(vec![
+      repo_mod(
+        "app",
+        "1.0.0",
+        vec![
+          dependency("gone", None, false),
+          dependency("lib", Some(">=2"), false),
+        ],
+      ),
+      repo_mod("lib", "1.2.0", vec![]),
+    ])];
+
+    let error = Resolver::new(&repos, &[])
+      .plan_install("app")
+      .unwrap_err()
+      .to_string();
+    assert!(error.contains("gone (required by app)"));
+    assert!(error.contains("lib (required by app) >=2"));
+  }
+
+  #[test]
+  fn test_plan_rejects_disjoint_ranges_in_a_diamond() {
+    let mut lib = repo_mod("lib", "2.0.0", vec![]);
+    lib.versions.push(ModVersion {
+      version: "1.4.0".to_string(),
+      downloads: vec![Download {
+        label: "Download".to_string(),
+        url: "https://example.com/lib-1.4.0.zip".to_string(),
+      }],
+      game_version: None,
+      updated_at: None,
+    });
+    let repos = vec![repository(vec![
+      repo_mod(
+        "app",
+        "1.0.0",
+        vec![
+          dependency("left", None, false),
+          dependency("right", None, false),
+        ],
+      ),
+      repo_mod("left", "1.0.0", vec![dependency("lib", Some("<2"), false)]),
+      repo_mod(
+        "right",
+        "1.0.0",
+        vec![dependency("lib", Some(">=2"), false)],
+      ),
+      lib,
+    ])];
+
+    let error = Resolver::new(&repos, &[]).plan_install("app").unwrap_err();
+    assert_eq!(error.code(), "dependency");
+    assert!(error
+      .to_string()
+      .contains("right needs lib >=2, but the plan uses lib 1.4.0"));
+  }
+
+  #[test]
+  fn test_dependents_of() {
+    let repos = vec![repository(vec![
+      repo_mod("app", "1.0.0", vec![dependency("lib", None, false)]),
+      repo_mod("tool", "1.0.0", vec![dependency("lib", None, true)]),
+      repo_mod("lib", "1.0.0", vec![]),
+    ])];
+    let installed = vec![
+      installed("app", "1.0.0"),
+      installed("tool", "1.0.0"),
+      installed("lib", "1.0.0"),
+    ];
+
+    let dependents = Resolver::new(&repos, &installed).dependents_of("lib");
+    assert_eq!(dependents, vec!["app".to_string()]);
+  }
+
+  #[test]
+  fn test_plan_uses_providers() {
+    let mut fork = repo_mod("hook-fork", "1.0.0", vec![]);
+    fork.provides = vec!["hook".to_string()];
+    let repos = vec![repository(vec![
+      repo_mod("app", "1.0.0", vec![dependency("hook", None, false)]),
+      fork,
+    ])];
+
+    let plan = Resolver::new(&repos, &[]).plan_install("app").unwrap();
+    let order: Vec<&str> = plan.steps.iter().map(|s| s.mod_id.as_str()).collect();
+    assert_eq!(order, vec!["hook-fork", "app"]);
+  }
+
+  #[test]
+  fn test_plan_installs_a_provider_once() {
+    let mut fork = repo_mod("hook-fork", "1.0.0", vec![]);
+    fork.provides = vec!["hook".to_string()];
+    let repos = vec![repository(vec![
+      repo_mod(
+        "app",
+        "1.0.0",
+        vec![
+          dependency("hook-fork", None, false),
+          dependency("hook", None, false),
+        ],
+      ),
+      fork,
+    ])];
+
+    let plan = Resolver::new(&repos, &[]).plan_install("app").unwrap();
+    let order: Vec<&str> = plan.steps.iter().map(|s| s.mod_id.as_str()).collect();
+    assert_eq!(order, vec!["hook-fork", "app"]);
+  }
+
+  #[test]
+  fn test_plan_checks_the_version_of_an_installed_provider() {
+    let mut fork = repo_mod("hook-fork", "1.0.0", vec![]);
+    fork.provides = vec!["hook".to_string()];
+    let repos = vec![repository(vec![
+      repo_mod("app", "1.0.0", vec![dependency("hook", Some(">=2"), false)]),
+      fork,
+      repo_mod("hook", "2.1.0", vec![]),
+    ])];
+    let installed = vec![installed("hook-fork", "1.0.0")];
+
+    let plan = Resolver::new(&repos, &installed)
+      .plan_install("app")
+      .unwrap();
+    let order: Vec<&str> = plan.steps.iter().map(|s| s.mod_id.as_str()).collect();
+    assert_eq!(order, vec!["hook", "app"]);
+    assert!(plan.satisfied.is_empty());
+  }
+
+  #[test]
+  fn test_dependents_of_ignores_dependencies_another_provider_satisfies() {
+    let mut fork = repo_mod("hook-fork", "1.0.0", vec![]);
+    fork.provides = vec!["hook".to_string()];
+    let repos = vec![repository(vec![
+      repo_mod("app", "1.0.0", vec![dependency("hook", None, false)]),
+      repo_mod("hook", "1.0.0", vec![]),
+      fork,
+    ])];
+    let installed = vec![
+      installed("app", "1.0.0"),
+      installed("hook", "1.0.0"),
+      installed("hook-fork", "1.0.0"),
+    ];
+
+    let resolver = Resolver::new(&repos, &installed);
+    assert!(resolver.dependents_of("hook").is_empty());
+    assert!(resolver.dependents_of("hook-fork").is_empty());
+
+    let without_fork = &installed[..2];
+    let resolver = Resolver::new(&repos, without_fork);
+    assert_eq!(resolver.dependents_of("hook"), vec!["app".to_string()]);
+  }
+
+  #[test]
+  fn test_plan_reports_incompatibilities() {
+    let mut app = repo_mod("app", "1.0.0", vec![]);
+    app.conflicts = vec![conflict("other", Some("<2"))];
+    let repos = vec![repository(vec![app, repo_mod("other", "1.0.0", vec![])])];
+
+    let plan = Resolver::new(&repos, &[installed("other", "1.5.0")])
+      .plan_install("app")
+      .unwrap();
+    assert_eq!(
+      plan.incompatibilities,
+      vec![Incompatibility {
+        mod_id: "app".to_string(),
+        conflicts_with: "other".to_string(),
+        version: Some("<2".to_string()),
+      }]
+    );
+
+    let plan = Resolver::new(&repos, &[installed("other", "2.0.0")])
+      .plan_install("app")
+      .unwrap();
+    assert!(plan.incompatibilities.is_empty());
+  }
+
+  #[test]
+  fn test_installed_incompatibilities() {
+    let mut first = repo_mod("first-hook", "1.0.0", vec![]);
+    first.conflicts = vec![conflict("controller-hook", None)];
+    let mut second = repo_mod("second-hook", "1.0.0", vec![]);
+    second.provides = vec!["controller-hook".to_string()];
+    let repos = vec![repository(vec![first, second])];
+    let installed = vec![
+      installed("first-hook", "1.0.0"),
+      installed("second-hook", "1.0.0"),
+    ];
+
+    let found = Resolver::new(&repos, &installed).installed_incompatibilities();
+    assert_eq!(found.len(), 1);
+    assert_eq!(found[0].conflicts_with, "second-hook");
+  }
+
+  #[tokio::test]
+  async fn test_execute_install_plan_upgrades_in_place_and_reports_skipped_steps() {
+    let dirs = test_dirs::isolate();
+    let game_dir = tempfile::tempdir().unwrap();
+    std::fs::create_dir_all(game_dir.path().join("BepInEx/core")).unwrap();
+    std::fs::write(game_dir.path().join("BepInEx/core/BepInEx.dll"), b"core").unwrap();
+    let file_url = |name: &str| {
+      let path = dirs.path().join(name);
+      std::fs::write(&path, name).unwrap();
+      reqwest::Url::from_file_path(path).unwrap().to_string()
+    };
+    // The mods come from a local repository in the same directory as their files
+    let catalog = Catalog::build(
+      vec![(
+        repository(vec![
+          repo_mod("a", "2.0.0", vec![]),
+          repo_mod("b", "1.0.0", vec![]),
+          repo_mod("c", "1.0.0", vec![]),
+        ]),
+        dirs.path().to_string_lossy().to_string(),
+      )],
+      &[],
+    );
+    install_tracked_version(
+      "a",
+      "a",
+      "1.0.0",
+      &catalog.download_source("a", &file_url("a-1.txt")),
+      game_dir.path(),
+      true,
+      ConflictPolicy::Abort,
+    )
+    .await
+    .unwrap();
+
+    let step = |mod_id: &str, version: &str, download_url: String| PlannedInstall {
+      mod_id: mod_id.to_string(),
+      title: mod_id.to_string(),
+      version: version.to_string(),
+      repo_id: "main".to_string(),
+      download_url,
+    };
+    let missing = reqwest::Url::from_file_path(dirs.path().join("missing.txt"))
+      .unwrap()
+      .to_string();
+    let plan = InstallPlan {
+      steps: vec![
+        step("a", "2.0.0", file_url("a-2.txt")),
+        step("b", "1.0.0", missing),
+        step("c", "1.0.0", file_url("c-1.txt")),
+      ],
+      ..Default::default()
+    };
+
+    let result = execute_install_plan(&plan, game_dir.path(), &catalog).await;
+
+    assert_eq!(result.installed, vec!["a"]);
+    assert_eq!(result.failed[0].mod_id, "b");
+    assert_eq!(result.skipped, vec!["c"]);
+    let upgraded = get_installed_mod("a", game_dir.path()).unwrap().unwrap();
+    assert_eq!(upgraded.version, "2.0.0");
+    assert_eq!(upgraded.history.len(), 1);
+    assert_eq!(upgraded.installed_files.len(), 1);
+    assert!(get_installed_mod("b", game_dir.path()).unwrap().is_none());
+  }
+}
@@ -0,0 +1,269 @@
+use std::sync::OnceLock;
+
+use regex::Regex;
+use schemars::JsonSchema;
+use serde::{Deserialize, Deserializer, Serialize};
+use serde_json::{Map, Value};
+
+use crate::error::{Result, SilkSpoolError};
+
+/// The version of the `mods.json` format this build writes and fully understands
+pub const SCHEMA_VERSION: u32 = 2;
+
+/// Documents without a `schema_version` predate versioning
+const UNVERSIONED_SCHEMA: u32 = 1;
+
+/// A mod repository, as published in a `mods.json` file
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ModRepository {
+  /// Version of the repository format; documents without one are read as version 1
+  #[serde(default = "unversioned_schema")]
+  pub schema_version: u32,
+  /// Unique ID made of letters, digits, '-', '_' and '.'
+  pub repo_id: String,
+  pub name: String,
+  /// Revision of the repository contents, starting at 1
+  pub version: u32,
+  pub mods: Vec<Mod>,
+  /// Fields added by newer schema versions
+  #[serde(flatten)]
+  pub extra: Map<String, Value>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct Mod {
+  pub id: String,
+  pub title: String,
+  /// Semver version of the current release
+  pub version: String,
+  pub description: String,
+  pub requirements: Vec<String>,
+  pub images: Vec<String>,
+  pub downloads: Vec<Download>,
+  pub homepage: Option<String>,
+  pub authors: Vec<String>,
+  pub game_version: String,
+  pub updated_at: String,
+  #[serde(default)]
+  pub dependencies: Vec<Dependency>,
+  /// Mods that break when installed together with this one
+  #[serde(default)]
+  pub conflicts: Vec<ModConflict>,
+  /// Other mod IDs this mod can stand in for when resolving dependencies
+  #[serde(default)]
+  pub provides: Vec<String>,
+  /// Earlier releases that can still be installed
+  #[serde(default)]
+  pub versions: Vec<ModVersion>,
+  /// Fields added by newer schema versions
+  #[serde(flatten)]
+  pub extra: Map<String, Value>,
+}
+
+impl Mod {
+  /// Get the downloads for a specific version, current or historical
+  pub fn downloads_for_version(&self, version: &str) -> Option<&[Download]> {
+    if self.version == version {
+      return Some(&self.downloads);
+    }
+
+    self
+      .versions
+      .iter()
+      .find(|v| v.version == version)
+      .map(|v| v.downloads.as_slice())
+  }
+}
+
+/// A past release of a mod
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ModVersion {
+  pub version: String,
+  pub downloads: Vec<Download>,
+  #[serde(default)]
+  pub game_version: Option<String>,
+  #[serde(default)]
+  pub updated_at: Option<String>,
+}
+
+/// A mod that cannot be installed alongside another
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ModConflict {
+  /// ID of the incompatible mod, or of something it provides
+  pub id: String,
+  /// Semver range of the incompatible versions; all versions when absent
+  #[serde(default)]
+  pub version: Option<String>,
+}
+
+/// A mod another mod needs in order to work
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct Dependency {
+  /// ID of the required mod
+  pub id: String,
+  /// Semver range the required mod must satisfy, e.g. `>=1.2, <2`
+  #[serde(default)]
+  pub version: Option<String>,
+  #[serde(default)]
+  pub optional: bool,
+  /// ID of the repository expected to provide the mod
+  #[serde(default)]
+  pub repo: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct Download {
+  pub label: String,
+  pub url: String,
+}
+
+/// Generate the JSON Schema describing repository documents
+pub fn repository_json_schema() -> Result<String> {
+  let schema = schemars::schema_for!(ModRepository);
+  serde_json::to_string_pretty(&schema)
+    .map_err(|e| SilkSpoolError::other(format!("Failed to serialize schema: {}", e)))
+}
+
+fn unversioned_schema() -> u32 {
+  UNVERSIONED_SCHEMA
+}
+
+/// Parse a repository document of any schema version
+///
+/// Older documents are migrated to the current version. Documents from newer versions are read as
+/// far as this version understands them: unknown fields are kept, but otherwise ignored.
+pub fn parse_repository(json: &str) -> Result<ModRepository> {
+  let document: Value =
+    serde_json::from_str(json).map_err(|e| SilkSpoolError::parse("repository JSON", e))?;
+  repository_from_value(document)
+}
+
+/// Read a repository from parsed JSON, migrating it from older schema versions
+pub fn repository_from_value(mut document: Value) -> Result<ModRepository> {
+  let schema_version = document
+    .get("schema_version")
+    .and_then(Value::as_u64)
+    .map(|v| v as u32)
+    .unwrap_or(UNVERSIONED_SCHEMA);
+
+  if schema_version < 2 {
+    migrate_v1(&mut document);
+  }
+
+  let mut repository: ModRepository =
+    serde_json::from_value(document).map_err(|e| SilkSpoolError::parse("repository JSON", e))?;
+
+  // Migrated documents are now in the current format; newer ones keep their version
+  repository.schema_version = repository.schema_version.max(SCHEMA_VERSION);
+
+  Ok(repository)
+}
+
+/// Deserialize a repository stored inside another document, such as a cache file
+pub fn deserialize_repository<'de, D>(deserializer: D) -> Result<ModRepository, D::Error>
+where
+  D: Deserializer<'de>,
+{
+  let document = Value::deserialize(deserializer)?;
+  repository_from_value(document).map_err(serde::de::Error::custom)
+}
+
+/// Version 1 mods had no `version` field; it was read from the download labels instead
+fn migrate_v1(document: &mut Value) {
+  static VERSION_PATTERN: OnceLock<Regex> = OnceLock::new();
+  let pattern = VERSION_PATTERN.get_or_init(|| Regex::new(r"v?(\d+\.\d+\.\d+)").unwrap());
+
+  let Some(mods) = document.get_mut("mods").and_then(Value::as_array_mut) else {
+    return;
+  };
+
+  for mod_item in mods.iter_mut().filter_map(Value::as_object_mut) {
+    if mod_item.contains_key("version") {
+      continue;
+    }
+
+    let version = mod_item
+      .get("downloads")
+      .and_then(Value::as_array)
+      .into_iter()
+      .flatten()
+      .filter_map(|download| download.get("label").and_then(Value::as_str))
+      .find_map(|label| pattern.captures(label).map(|c| c[1].to_string()))
+      .unwrap_or_else(|| "0.0.0".to_string());
+
+    mod_item.insert("version".to_string(), Value::String(version));
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::{fs, path::Path};
+
+  use super::*;
+
+  /// The schema published for repository authors must match the Rust types
+  ///
+  /// Run the tests with `UPDATE_SCHEMA=1` to regenerate it after changing the format.
+  #[test]
+  fn test_published_schema_is_up_to_date() {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("../public/mods.schema.json");
+    let schema = repository_json_schema().unwrap() + "\n";
+
+    if std::env::var_os("UPDATE_SCHEMA").is_some() {
+      fs::write(&path, &schema).unwrap();
+    }
+
+    let published = fs::read_to_string(&path).unwrap_or_default();
+    assert!(
+      published == schema,
+      "public/mods.schema.json is out of date, run the tests with UPDATE_SCHEMA=1"
+    );
+  }
+
+  #[test]
+  fn test_v1_repositories_are_migrated() {
+    let repository = parse_repository(
+      r#"{
+        "repo_id": "old",
+        "name": "Old",
+        "version": 3,
+        "mods": [{
+          "id": "a", "title": "A", "description": "Test", "requirements": [], "images": [],
+          "downloads": [{"label": "Download v1.4.2", "url": "https://example.com/a.zip"}],
+          "homepage": null, "authors": [], "game_version": "1.0", "updated_at": "2024-01-01"
+        }]
+      }"#,
+    )
+    .unwrap();
+
+    assert_eq!(repository.schema_version, SCHEMA_VERSION);
+    assert_eq!(repository.mods[0].version, "1.4.2");
+  }
+
+  #[test]
+  fn test_newer_schemas_keep_unknown_fields() {
+    let repository = parse_repository(
+      r#"{
+        "schema_version": 7,
+        "repo_id": "new",
+        "name": "New",
+        "version": 1,
+        "maintainers": ["someone"],
+        "mods": [{
+          "id": "a", "title": "A", "version": "2.0.0", "description": "Test",
+          "requirements": [], "images": [], "downloads": [], "homepage": null, "authors": [],
+          "game_version": "1.0", "updated_at": "2024-01-01", "license": "MIT"
+        }]
+      }"#,
+    )
+    .unwrap();
+
+    assert_eq!(repository.schema_version, 7);
+    assert!(repository.extra.contains_key("maintainers"));
+    assert_eq!(repository.mods[0].extra["license"], "MIT");
+
+    let round_trip = serde_json::to_value(&repository).unwrap();
+    assert_eq!(round_trip["maintainers"][0], "someone");
+    assert_eq!(round_trip["mods"][0]["license"], "MIT");
+  }
+}
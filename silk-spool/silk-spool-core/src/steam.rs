@@ -3,6 +3,8 @@ use std::path::{Path, PathBuf};
 use serde::{Deserialize, Serialize};
 use walkdir::WalkDir;
 
+use crate::error::{Result, SilkSpoolError};
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SteamLibrary {
   pub path: PathBuf,
@@ -24,16 +26,18 @@ pub struct GameDetectionResult {
 }
 
 /// Get Steam libraries for Windows
-pub fn get_steam_libraries_windows() -> Result<Vec<PathBuf>, String> {
+pub fn get_steam_libraries_windows() -> Result<Vec<PathBuf>> {
   let steam_path = Path::new("C:\\Program Files (x86)\\Steam");
   let library_folders_path = steam_path.join("steamapps").join("libraryfolders.vdf");
 
   if !library_folders_path.exists() {
-    return Err("Steam libraryfolders.vdf not found".to_string());
+    return Err(SilkSpoolError::GameNotFound {
+      reason: "Steam libraryfolders.vdf not found".to_string(),
+    });
   }
 
   let content = std::fs::read_to_string(&library_folders_path)
-    .map_err(|e| format!("Failed to read libraryfolders.vdf: {}", e))?;
+    .map_err(|e| SilkSpoolError::io("read", &library_folders_path, e))?;
 
   let mut libraries = Vec::new();
 
@@ -62,8 +66,9 @@ pub fn get_steam_libraries_windows() -> Result<Vec<PathBuf>, String> {
 }
 
 /// Get Steam libraries for macOS
-pub fn get_steam_libraries_macos() -> Result<Vec<PathBuf>, String> {
-  let home_dir = dirs::home_dir().ok_or("Could not find home directory")?;
+pub fn get_steam_libraries_macos() -> Result<Vec<PathBuf>> {
+  let home_dir =
+    dirs::home_dir().ok_or_else(|| SilkSpoolError::other("Could not find home directory"))?;
   let steam_path = home_dir
     .join("Library")
     .join("Application Support")
@@ -102,7 +107,7 @@ pub fn get_steam_libraries_macos() -> Result<Vec<PathBuf>, String> {
 }
 
 /// Get Steam libraries based on the current platform
-pub fn get_steam_libraries() -> Result<Vec<PathBuf>, String> {
+pub fn get_steam_libraries() -> Result<Vec<PathBuf>> {
   #[cfg(target_os = "windows")]
   {
     get_steam_libraries_windows()
@@ -115,12 +120,15 @@ pub fn get_steam_libraries() -> Result<Vec<PathBuf>, String> {
 
   #[cfg(not(any(target_os = "windows", target_os = "macos")))]
   {
-    Err("Unsupported platform".to_string())
+    Err(SilkSpoolError::GameNotFound {
+      reason: "Unsupported platform".to_string(),
+    })
   }
 }
 
 /// Find Hollow Knight: Silksong installation in Steam libraries
-pub fn find_silksong_installation() -> Result<GameDetectionResult, String> {
+#[tracing::instrument]
+pub fn find_silksong_installation() -> Result<GameDetectionResult> {
   let libraries = get_steam_libraries()?;
   let mut result = GameDetectionResult {
     found: false,
@@ -137,6 +145,7 @@ pub fn find_silksong_installation() -> Result<GameDetectionResult, String> {
 
   for library_path in libraries {
     let steamapps_path = library_path.join("steamapps").join("common");
+    tracing::debug!(library = %library_path.display(), "Searching Steam library");
 
     if !steamapps_path.exists() {
       continue;
@@ -149,15 +158,15 @@ pub fn find_silksong_installation() -> Result<GameDetectionResult, String> {
 
     // Walk through common directory to find game folders
     for entry in WalkDir::new(&steamapps_path).max_depth(1) {
-      let entry = entry.map_err(|e| format!("Failed to read directory: {}", e))?;
+      let entry = entry.map_err(|e| SilkSpoolError::io("read directory", &steamapps_path, e))?;
 
       if entry.file_type().is_dir() {
         let dir_name = entry.file_name().to_string_lossy();
 
         // Check if this directory matches our Silksong patterns
         for pattern in &silksong_patterns {
-          let regex =
-            regex::Regex::new(pattern).map_err(|e| format!("Invalid regex pattern: {}", e))?;
+          let regex = regex::Regex::new(pattern)
+            .map_err(|e| SilkSpoolError::other(format!("Invalid regex pattern: {}", e)))?;
 
           if regex.is_match(&dir_name) {
             // Check if this is a valid game installation
@@ -182,6 +191,10 @@ pub fn find_silksong_installation() -> Result<GameDetectionResult, String> {
 }
 
 /// Check if a directory contains a valid game installation
+#[cfg_attr(
+  not(any(target_os = "windows", target_os = "macos")),
+  allow(unused_variables)
+)]
 fn is_valid_game_installation(path: &Path) -> bool {
   #[cfg(target_os = "windows")]
   {
@@ -220,16 +233,8 @@ fn is_valid_game_installation(path: &Path) -> bool {
 
 #[cfg(test)]
 mod tests {
-  use super::*;
-
   #[test]
   fn test_silksong_patterns() {
-    let patterns = [
-      r"(?i)silksong",
-      r"(?i)hollow.?knight.?silksong",
-      r"(?i)hollow knight silksong",
-    ];
-
     let test_cases = [
       "Hollow Knight Silksong",
       "hollow knight silksong",
@@ -0,0 +1,48 @@
+use std::fs;
+
+use crate::{
+  error::{Result, SilkSpoolError},
+  schema::parse_repository,
+  validation::validate_repository,
+};
+
+/// Test function to validate our sample repository JSON
+pub fn test_sample_repository() -> Result<()> {
+  // Read the built-in mods.json file
+  let json_content = fs::read_to_string("../public/mods.json")
+    .map_err(|e| SilkSpoolError::io("read built-in", "../public/mods.json", e))?;
+
+  // Parse the JSON
+  let repository = parse_repository(&json_content)?;
+
+  // Validate the repository structure
+  validate_repository(&repository, None).into_result()?;
+  tracing::info!(
+    name = %repository.name,
+    repo_id = %repository.repo_id,
+    schema_version = repository.schema_version,
+    version = repository.version,
+    mods = repository.mods.len(),
+    "Repository loaded successfully"
+  );
+
+  // Validate each mod
+  for mod_item in &repository.mods {
+    tracing::info!(
+      mod_id = %mod_item.id,
+      title = %mod_item.title,
+      version = %mod_item.version,
+      authors = %mod_item.authors.join(", "),
+      requirements = %mod_item.requirements.join(", "),
+      images = mod_item.images.len(),
+      downloads = mod_item.downloads.len(),
+      homepage = mod_item.homepage.as_deref().unwrap_or("None"),
+      game_version = %mod_item.game_version,
+      updated_at = %mod_item.updated_at,
+      "Checked mod"
+    );
+  }
+
+  tracing::info!("Repository structure is valid");
+  Ok(())
+}
@@ -0,0 +1,2 @@
+// Test modules
+mod basic_tests;
@@ -1,5 +1,7 @@
 use serde::{Deserialize, Serialize};
 
+use crate::{conflicts::FileConflict, error::SilkSpoolError};
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BepInExStatus {
   pub present: bool,
@@ -14,34 +16,6 @@ pub struct GameStatus {
   pub bepinex: BepInExStatus,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct Mod {
-  pub id: String,
-  pub title: String,
-  pub description: String,
-  pub requirements: Vec<String>,
-  pub images: Vec<String>,
-  pub downloads: Vec<Download>,
-  pub homepage: Option<String>,
-  pub authors: Vec<String>,
-  pub game_version: String,
-  pub updated_at: String,
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct Download {
-  pub label: String,
-  pub url: String,
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct ModRepository {
-  pub repo_id: String,
-  pub name: String,
-  pub version: u32,
-  pub mods: Vec<Mod>,
-}
-
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AppConfig {
   pub game_path: Option<String>,
@@ -61,6 +35,8 @@ pub struct InstallResult {
   pub installed_files: Vec<String>,
   #[serde(rename = "modFolderName")]
   pub mod_folder_name: Option<String>,
+  #[serde(default)]
+  pub conflicts: Vec<FileConflict>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -78,6 +54,46 @@ pub struct InstalledMod {
   pub game_path: String,
   #[serde(rename = "downloadUrl")]
   pub download_url: Option<String>,
+  #[serde(default = "default_enabled")]
+  pub enabled: bool,
+  /// Version the mod is held at, skipped by bulk updates
+  #[serde(default, rename = "pinnedVersion")]
+  pub pinned_version: Option<String>,
+  /// Versions this install replaced, oldest first
+  #[serde(default)]
+  pub history: Vec<ModVersionRecord>,
+}
+
+/// A version of a mod that was replaced by a later install
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModVersionRecord {
+  pub version: String,
+  #[serde(rename = "installedFiles")]
+  pub installed_files: Vec<String>,
+  #[serde(rename = "downloadUrl")]
+  pub download_url: Option<String>,
+  #[serde(rename = "replacedAt")]
+  pub replaced_at: String,
+  /// Directory holding a copy of the version's files, when they were archived
+  #[serde(default, rename = "archivePath")]
+  pub archive_path: Option<String>,
+}
+
+fn default_enabled() -> bool {
+  true
+}
+
+/// A mod that could not be processed as part of a larger operation
+#[derive(Debug, Clone, Serialize)]
+pub struct ModActionFailure {
+  pub mod_id: String,
+  pub error: SilkSpoolError,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InstalledModEntry {
+  pub name: String,
+  pub enabled: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
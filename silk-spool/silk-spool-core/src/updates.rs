@@ -0,0 +1,585 @@
+use std::{collections::HashSet, fs, path::Path};
+
+use serde::{Deserialize, Serialize};
+use tempfile::TempDir;
+
+use crate::{
+  catalog::Catalog,
+  conflicts::ConflictPolicy,
+  error::{Result, SilkSpoolError},
+  installed_mods::{get_installed_mod, get_installed_mods_for_game, restore_installed_mod},
+  installer::{
+    archive_tracked_files, fetch_cached_download, install_tracked_version, restore_archived_files,
+    uninstall_tracked_mod,
+  },
+  resolver::{describe_incompatibilities, mod_version, parse_version, PlannedInstall, Resolver},
+  schema::{Dependency, Mod, ModRepository},
+  types::InstalledMod,
+};
+
+/// A newer version available for an installed mod
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ModUpdate {
+  pub mod_id: String,
+  pub title: String,
+  pub installed_version: String,
+  pub available_version: String,
+  pub repo_id: String,
+  pub download_url: String,
+  /// True when the mod is held at its installed version
+  pub pinned: bool,
+}
+
+/// What happened to one mod during a bulk update
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum UpdateStatus {
+  /// Upgraded to the new version
+  Updated,
+  /// Newly installed because an updated mod depends on it
+  Installed,
+  /// Held at its pinned version
+  Pinned,
+  /// This mod could not be updated
+  Failed,
+  /// Left at its previous version because another update failed
+  Cancelled,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ModUpdateResult {
+  pub mod_id: String,
+  pub from_version: Option<String>,
+  pub to_version: String,
+  pub status: UpdateStatus,
+  pub error: Option<SilkSpoolError>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct UpdateAllResult {
+  /// True when every unpinned update was applied
+  pub success: bool,
+  pub results: Vec<ModUpdateResult>,
+  /// Problems met while restoring the previous versions after a failure
+  pub rollback_errors: Vec<String>,
+}
+
+/// Find the newest release of a mod across all repositories
+fn newest_release<'a>(
+  repositories: &'a [ModRepository],
+  mod_id: &str,
+) -> Option<(&'a ModRepository, &'a Mod)> {
+  repositories
+    .iter()
+    .flat_map(|repo| repo.mods.iter().map(move |m| (repo, m)))
+    .filter(|(_, m)| m.id == mod_id)
+    .filter_map(|(repo, m)| mod_version(m).map(|version| (version, repo, m)))
+    .max_by(|(a, _, _), (b, _, _)| a.cmp(b))
+    .map(|(_, repo, m)| (repo, m))
+}
+
+/// Compare every installed mod against the repositories and list the available upgrades
+///
+/// Installed versions that are not valid semver are reported whenever the repository
+/// offers a different version, so they can be moved onto a tracked release.
+pub fn check_updates(repositories: &[ModRepository], installed: &[InstalledMod]) -> Vec<ModUpdate> {
+  installed
+    .iter()
+    .filter_map(|installed_mod| {
+      let (repo, candidate) = newest_release(repositories, &installed_mod.mod_id)?;
+      let available = mod_version(candidate)?;
+
+      let is_newer = match parse_version(&installed_mod.version) {
+        Some(current) => available > current,
+        None => installed_mod.version != candidate.version,
+      };
+      if !is_newer {
+        return None;
+      }
+
+      let download = candidate.downloads.first()?;
+
+      Some(ModUpdate {
+        mod_id: installed_mod.mod_id.clone(),
+        title: candidate.title.clone(),
+        installed_version: installed_mod.version.clone(),
+        available_version: candidate.version.clone(),
+        repo_id: repo.repo_id.clone(),
+        download_url: download.url.clone(),
+        pinned: installed_mod.pinned_version.is_some(),
+      })
+    })
+    .collect()
+}
+
+/// Upgrade every outdated mod of one game that is not pinned, together with any new dependencies
+///
+/// All downloads are fetched and the outgoing versions backed up before anything changes. If one
+/// step fails, every mod already touched is put back, so the install is never left half updated.
+#[tracing::instrument(skip_all, err)]
+pub async fn update_all(catalog: &Catalog, game_path: &Path) -> Result<UpdateAllResult> {
+  let repositories = &catalog.as_repositories();
+  let installed = get_installed_mods_for_game(game_path)?;
+  let mut result = UpdateAllResult {
+    success: true,
+    ..Default::default()
+  };
+
+  let mut targets = Vec::new();
+  for update in check_updates(repositories, &installed) {
+    if update.pinned {
+      result.results.push(ModUpdateResult {
+        mod_id: update.mod_id,
+        from_version: Some(update.installed_version),
+        to_version: update.available_version,
+        status: UpdateStatus::Pinned,
+        error: None,
+      });
+    } else {
+      targets.push(Dependency {
+        id: update.mod_id,
+        version: Some(format!(
+          "={}",
+          update.available_version.trim().trim_start_matches('v')
+        )),
+        optional: false,
+        repo: Some(update.repo_id),
+      });
+    }
+  }
+
+  if targets.is_empty() {
+    return Ok(result);
+  }
+
+  // Mods being updated must not count as satisfying each other's dependencies
+  let remaining: Vec<InstalledMod> = installed
+    .iter()
+    .filter(|m| !targets.iter().any(|target| target.id == m.mod_id))
+    .cloned()
+    .collect();
+  let plan = Resolver::new(repositories, &remaining).plan_targets(&targets)?;
+
+  if !plan.incompatibilities.is_empty() {
+    return Err(describe_incompatibilities(&plan.incompatibilities));
+  }
+
+  let previous: Vec<Option<InstalledMod>> = plan
+    .steps
+    .iter()
+    .map(|step| installed.iter().find(|m| m.mod_id == step.mod_id).cloned())
+    .collect();
+
+  if let Some(held) = previous
+    .iter()
+    .flatten()
+    .find(|m| m.pinned_version.is_some())
+  {
+    return Err(SilkSpoolError::Dependency {
+      mod_id: Some(held.mod_id.clone()),
+      message: format!(
+        "Updating would change {}, which is pinned at {}",
+        held.mod_id,
+        held.pinned_version.as_deref().unwrap_or_default()
+      ),
+    });
+  }
+
+  tracing::info!(steps = plan.steps.len(), "Updating mods");
+  let backup = TempDir::new()
+    .map_err(|e| SilkSpoolError::io("create backup directory in", std::env::temp_dir(), e))?;
+  let backup_root = backup.path();
+
+  let failure = match prepare_update(&plan.steps, &previous, backup_root, catalog).await {
+    Err(failure) => Some(failure),
+    Ok(()) => {
+      apply_update(
+        &plan.steps,
+        &previous,
+        backup_root,
+        game_path,
+        catalog,
+        &mut result,
+      )
+      .await
+    }
+  };
+
+  for (step, previous_mod) in plan.steps.iter().zip(&previous) {
+    let (status, error) = match &failure {
+      Some((mod_id, error)) if *mod_id == step.mod_id => {
+        (UpdateStatus::Failed, Some(error.clone()))
+      }
+      Some(_) => (UpdateStatus::Cancelled, None),
+      None if previous_mod.is_some() => (UpdateStatus::Updated, None),
+      None => (UpdateStatus::Installed, None),
+    };
+
+    result.results.push(ModUpdateResult {
+      mod_id: step.mod_id.clone(),
+      from_version: previous_mod.as_ref().map(|m| m.version.clone()),
+      to_version: step.version.clone(),
+      status,
+      error,
+    });
+  }
+  result.success = failure.is_none();
+
+  Ok(result)
+}
+
+/// Download every update and back up the versions they replace, changing nothing in the game
+async fn prepare_update(
+  steps: &[PlannedInstall],
+  previous: &[Option<InstalledMod>],
+  backup_root: &Path,
+  catalog: &Catalog,
+) -> Result<(), (String, SilkSpoolError)> {
+  for step in steps {
+    fetch_cached_download(&catalog.download_source(&step.mod_id, &step.download_url))
+      .await
+      .map_err(|error| (step.mod_id.clone(), error))?;
+  }
+
+  for previous_mod in previous.iter().flatten() {
+    archive_tracked_files(previous_mod, &backup_root.join(&previous_mod.mod_id))
+      .map_err(|error| (previous_mod.mod_id.clone(), error))?;
+  }
+
+  Ok(())
+}
+
+/// Install every step, rolling all of them back if one fails
+async fn apply_update(
+  steps: &[PlannedInstall],
+  previous: &[Option<InstalledMod>],
+  backup_root: &Path,
+  game_path: &Path,
+  catalog: &Catalog,
+  result: &mut UpdateAllResult,
+) -> Option<(String, SilkSpoolError)> {
+  for (index, step) in steps.iter().enumerate() {
+    let enabled = previous[index].as_ref().is_none_or(|m| m.enabled);
+
+    if let Err(error) = install_tracked_version(
+      &step.mod_id,
+      &step.title,
+      &step.version,
+      &catalog.download_source(&step.mod_id, &step.download_url),
+      game_path,
+      enabled,
+      ConflictPolicy::Abort,
+    )
+    .await
+    {
+      tracing::warn!(mod_id = %step.mod_id, %error, "Update failed, rolling back");
+      result.rollback_errors = roll_back(
+        &steps[..=index],
+        &previous[..=index],
+        backup_root,
+        game_path,
+      );
+      return Some((step.mod_id.clone(), error));
+    }
+  }
+
+  None
+}
+
+/// Undo applied steps, newest first, putting back the versions that were there before
+fn roll_back(
+  steps: &[PlannedInstall],
+  previous: &[Option<InstalledMod>],
+  backup_root: &Path,
+  game_path: &Path,
+) -> Vec<String> {
+  let mut errors = Vec::new();
+
+  for (step, previous_mod) in steps.iter().zip(previous).rev() {
+    if let Ok(Some(current)) = get_installed_mod(&step.mod_id, game_path) {
+      // Versions archived by this update are not part of the history being restored
+      let kept: HashSet<&str> = previous_mod
+        .iter()
+        .flat_map(|m| &m.history)
+        .filter_map(|record| record.archive_path.as_deref())
+        .collect();
+      for archive in current
+        .history
+        .iter()
+        .filter_map(|record| record.archive_path.as_deref())
+        .filter(|archive| !kept.contains(archive))
+      {
+        let _ = fs::remove_dir_all(archive);
+      }
+
+      if let Err(e) = uninstall_tracked_mod(&step.mod_id, game_path) {
+        errors.push(format!("{}: {}", step.mod_id, e));
+      }
+    }
+
+    if let Some(previous_mod) = previous_mod {
+      let restored = restore_archived_files(previous_mod, &backup_root.join(&previous_mod.mod_id))
+        .and_then(|_| restore_installed_mod(previous_mod.clone()));
+      if let Err(e) = restored {
+        errors.push(format!("{}: {}", step.mod_id, e));
+      }
+    }
+  }
+
+  for error in &errors {
+    tracing::error!(%error, "Failed to roll back update");
+  }
+
+  errors
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::{
+    config::test_dirs,
+    installed_mods::add_installed_mod,
+    installer::DownloadSource,
+    schema::{Download, SCHEMA_VERSION},
+  };
+
+  fn repo_mod(id: &str, version: &str) -> Mod {
+    Mod {
+      id: id.to_string(),
+      title: id.to_string(),
+      version: version.to_string(),
+      description: String::new(),
+      requirements: vec![],
+      images: vec![],
+      downloads: vec![Download {
+        label: "Download".to_string(),
+        url: format!("https://example.com/{}-{}.zip", id, version),
+      }],
+      homepage: None,
+      authors: vec![],
+      game_version: "1.0".to_string(),
+      updated_at: "2024-01-01".to_string(),
+      dependencies: vec![],
+      conflicts: vec![],
+      provides: vec![],
+      versions: vec![],
+      extra: Default::default(),
+    }
+  }
+
+  fn repository(repo_id: &str, mods: Vec<Mod>) -> ModRepository {
+    ModRepository {
+      schema_version: SCHEMA_VERSION,
+      repo_id: repo_id.to_string(),
+      name: repo_id.to_string(),
+      version: 1,
+      mods,
+      extra: Default::default(),
+    }
+  }
+
+  /// A download listed by a local repository in the file's directory
+  fn local_source(url: String) -> DownloadSource {
+    let path = reqwest::Url::parse(&url).unwrap().to_file_path().unwrap();
+    DownloadSource {
+      url,
+      local_root: path.parent().map(Path::to_path_buf),
+    }
+  }
+
+  /// A catalog holding one local repository in `root`
+  fn local_catalog(repository: ModRepository, root: &Path) -> Catalog {
+    Catalog::build(vec![(repository, root.to_string_lossy().to_string())], &[])
+  }
+
+  fn installed(mod_id: &str, version: &str) -> InstalledMod {
+    InstalledMod {
+      mod_id: mod_id.to_string(),
+      mod_title: mod_id.to_string(),
+      version: version.to_string(),
+      installed_at: "2024-01-01T00:00:00Z".to_string(),
+      installed_files: vec![],
+      game_path: "/game".to_string(),
+      download_url: None,
+      enabled: true,
+      pinned_version: None,
+      history: vec![],
+    }
+  }
+
+  #[test]
+  fn test_check_updates_flags_pinned_mods() {
+    let repositories = vec![repository("main", vec![repo_mod("a", "2.0.0")])];
+    let mut held = installed("a", "1.0.0");
+    held.pinned_version = Some("1.0.0".to_string());
+
+    let updates = check_updates(&repositories, &[held]);
+
+    assert_eq!(updates.len(), 1);
+    assert!(updates[0].pinned);
+  }
+
+  #[test]
+  fn test_check_updates_uses_semver_ordering() {
+    let repositories = vec![
+      repository(
+        "main",
+        vec![repo_mod("a", "1.10.0"), repo_mod("b", "2.0.0")],
+      ),
+      repository("mirror", vec![repo_mod("a", "1.9.0")]),
+    ];
+    let installed = vec![installed("a", "1.9.0"), installed("b", "2.0.0")];
+
+    let updates = check_updates(&repositories, &installed);
+
+    assert_eq!(updates.len(), 1);
+    assert_eq!(updates[0].mod_id, "a");
+    assert_eq!(updates[0].available_version, "1.10.0");
+    assert_eq!(updates[0].repo_id, "main");
+  }
+
+  #[test]
+  fn test_check_updates_ignores_unknown_and_downgrades() {
+    let repositories = vec![repository("main", vec![repo_mod("a", "1.0.0")])];
+    let installed = vec![installed("a", "1.2.0"), installed("local-only", "0.1.0")];
+
+    assert!(check_updates(&repositories, &installed).is_empty());
+  }
+
+  #[test]
+  fn test_check_updates_reports_untracked_versions() {
+    let repositories = vec![repository("main", vec![repo_mod("a", "1.0.0")])];
+
+    let updates = check_updates(&repositories, &[installed("a", "1.0")]);
+
+    assert_eq!(updates.len(), 1);
+    assert_eq!(updates[0].installed_version, "1.0");
+  }
+
+  #[tokio::test]
+  async fn test_update_all_restores_earlier_mods_when_a_later_one_fails() {
+    let dirs = test_dirs::isolate();
+    let game_dir = tempfile::tempdir().unwrap();
+    let game_path = game_dir.path();
+    fs::create_dir_all(game_path.join("BepInEx/plugins")).unwrap();
+    let file_url = |name: &str, contents: &[u8]| {
+      let path = dirs.path().join(name);
+      fs::write(&path, contents).unwrap();
+      reqwest::Url::from_file_path(path).unwrap().to_string()
+    };
+
+    for id in ["a", "b"] {
+      let url = file_url(&format!("{}-1.0.0.txt", id), b"v1");
+      install_tracked_version(
+        id,
+        id,
+        "1.0.0",
+        &local_source(url),
+        game_path,
+        true,
+        ConflictPolicy::Abort,
+      )
+      .await
+      .unwrap();
+    }
+    let before = get_installed_mod("a", game_path).unwrap().unwrap();
+
+    // The new version of b is a plugin DLL whose file another mod already owns
+    add_installed_mod(
+      "other".to_string(),
+      "other".to_string(),
+      "1.0.0".to_string(),
+      vec![game_path
+        .join("BepInEx/plugins/b-2.0.0.dll")
+        .to_string_lossy()
+        .to_string()],
+      game_path.to_string_lossy().to_string(),
+      None,
+    )
+    .unwrap();
+    let mut new_a = repo_mod("a", "v2.0.0");
+    new_a.downloads[0].url = file_url("a-2.0.0.txt", b"v2");
+    let mut new_b = repo_mod("b", "2.0.0");
+    new_b.downloads[0].url = file_url("b-2.0.0.dll", b"v2");
+    let catalog = local_catalog(repository("main", vec![new_a, new_b]), dirs.path());
+
+    let result = update_all(&catalog, game_path).await.unwrap();
+
+    assert!(!result.success);
+    assert!(result.rollback_errors.is_empty());
+    let status = |id: &str| {
+      result
+        .results
+        .iter()
+        .find(|r| r.mod_id == id)
+        .map(|r| r.status)
+    };
+    assert_eq!(status("a"), Some(UpdateStatus::Cancelled));
+    assert_eq!(status("b"), Some(UpdateStatus::Failed));
+
+    let after = get_installed_mod("a", game_path).unwrap().unwrap();
+    assert_eq!(after.version, "1.0.0");
+    assert_eq!(after.installed_files, before.installed_files);
+    assert!(after.history.is_empty());
+    assert_eq!(fs::read(&after.installed_files[0]).unwrap(), b"v1");
+    let archived = walkdir::WalkDir::new(dirs.path().join("cache/archive"))
+      .into_iter()
+      .flatten()
+      .filter(|entry| entry.file_type().is_file())
+      .count();
+    assert_eq!(archived, 0);
+  }
+
+  #[tokio::test]
+  async fn test_update_all_only_updates_the_given_game() {
+    let dirs = test_dirs::isolate();
+    let file_url = |name: &str, contents: &[u8]| {
+      let path = dirs.path().join(name);
+      fs::write(&path, contents).unwrap();
+      reqwest::Url::from_file_path(path).unwrap().to_string()
+    };
+    let old_source = local_source(file_url("a-1.0.0.txt", b"v1"));
+    let games = [tempfile::tempdir().unwrap(), tempfile::tempdir().unwrap()];
+    for game in &games {
+      fs::create_dir_all(game.path().join("BepInEx/core")).unwrap();
+      fs::write(game.path().join("BepInEx/core/BepInEx.dll"), b"core").unwrap();
+    }
+    install_tracked_version(
+      "a",
+      "a",
+      "1.0.0",
+      &old_source,
+      games[0].path(),
+      true,
+      ConflictPolicy::Abort,
+    )
+    .await
+    .unwrap();
+    let mut new_a = repo_mod("a", "2.0.0");
+    new_a.downloads[0].url = file_url("a-2.0.0.txt", b"v2");
+    let catalog = local_catalog(repository("main", vec![new_a]), dirs.path());
+
+    let untouched = update_all(&catalog, games[1].path()).await.unwrap();
+    assert!(untouched.results.is_empty());
+
+    install_tracked_version(
+      "a",
+      "a",
+      "1.0.0",
+      &old_source,
+      games[1].path(),
+      true,
+      ConflictPolicy::Abort,
+    )
+    .await
+    .unwrap();
+    let result = update_all(&catalog, games[1].path()).await.unwrap();
+
+    assert!(result.success);
+    let updated = get_installed_mod("a", games[1].path()).unwrap().unwrap();
+    assert_eq!(updated.version, "2.0.0");
+    let kept = get_installed_mod("a", games[0].path()).unwrap().unwrap();
+    assert_eq!(kept.version, "1.0.0");
+    assert_eq!(fs::read(&kept.installed_files[0]).unwrap(), b"v1");
+  }
+}
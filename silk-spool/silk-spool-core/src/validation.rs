@@ -0,0 +1,411 @@
+use std::{collections::HashMap, fmt};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+  error::{Result, SilkSpoolError},
+  repository::validate_repo_id,
+  schema::{Dependency, Download, ModConflict, ModRepository, SCHEMA_VERSION},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Severity {
+  /// The repository cannot be used
+  Error,
+  /// The repository can be used, but something in it is probably a mistake
+  Warning,
+}
+
+/// One problem found in a repository
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ValidationIssue {
+  pub severity: Severity,
+  /// Location of the offending value, such as `mods[3].downloads[0].url`
+  pub path: String,
+  pub message: String,
+}
+
+impl fmt::Display for ValidationIssue {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    if self.path.is_empty() {
+      write!(f, "{}", self.message)
+    } else {
+      write!(f, "{}: {}", self.path, self.message)
+    }
+  }
+}
+
+/// Everything wrong with a repository, collected in one pass
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ValidationReport {
+  pub errors: Vec<ValidationIssue>,
+  pub warnings: Vec<ValidationIssue>,
+}
+
+impl ValidationReport {
+  /// Build a report for a document that could not be parsed at all
+  pub fn parse_failure(message: String) -> Self {
+    let mut report = Self::default();
+    report.error(String::new(), message);
+    report
+  }
+
+  /// True when the repository has no errors; warnings are allowed
+  pub fn is_valid(&self) -> bool {
+    self.errors.is_empty()
+  }
+
+  /// Turn the report into an error listing every problem, if there are any
+  pub fn into_result(self) -> Result<()> {
+    if self.is_valid() {
+      return Ok(());
+    }
+
+    let errors: Vec<String> = self.errors.iter().map(|e| e.to_string()).collect();
+    Err(SilkSpoolError::InvalidRepository {
+      message: format!("Invalid repository: {}", errors.join("; ")),
+    })
+  }
+
+  fn error(&mut self, path: String, message: impl Into<String>) {
+    self.errors.push(ValidationIssue {
+      severity: Severity::Error,
+      path,
+      message: message.into(),
+    });
+  }
+
+  fn warning(&mut self, path: String, message: impl Into<String>) {
+    self.warnings.push(ValidationIssue {
+      severity: Severity::Warning,
+      path,
+      message: message.into(),
+    });
+  }
+}
+
+/// Check a repository, collecting every error and warning rather than stopping at the first
+///
+/// `base` is the location relative URLs are resolved against. Relative paths pointing into a local
+/// repository are checked for existence; remote ones are not requested.
+pub fn validate_repository(repo: &ModRepository, base: Option<&reqwest::Url>) -> ValidationReport {
+  let mut report = ValidationReport::default();
+
+  if repo.schema_version > SCHEMA_VERSION {
+    report.warning(
+      "schema_version".to_string(),
+      format!(
+        "Schema version {} is newer than the supported version {}; unknown fields are ignored",
+        repo.schema_version, SCHEMA_VERSION
+      ),
+    );
+  }
+  if repo.repo_id.is_empty() {
+    report.error("repo_id".to_string(), "Repository ID cannot be empty");
+  } else if let Err(e) = validate_repo_id(&repo.repo_id) {
+    report.error("repo_id".to_string(), e.to_string());
+  }
+  if repo.name.is_empty() {
+    report.error("name".to_string(), "Repository name cannot be empty");
+  }
+  if repo.version == 0 {
+    report.error(
+      "version".to_string(),
+      "Repository version must be greater than 0",
+    );
+  }
+
+  let mut first_index: HashMap<&str, usize> = HashMap::new();
+
+  for (index, mod_item) in repo.mods.iter().enumerate() {
+    let path = format!("mods[{}]", index);
+
+    if mod_item.id.is_empty() {
+      report.error(format!("{}.id", path), "Mod ID cannot be empty");
+    } else if let Some(first) = first_index.get(mod_item.id.as_str()) {
+      report.warning(
+        format!("{}.id", path),
+        format!(
+          "Duplicate mod ID '{}', already used by mods[{}]",
+          mod_item.id, first
+        ),
+      );
+    } else {
+      first_index.insert(&mod_item.id, index);
+    }
+
+    if mod_item.title.is_empty() {
+      report.error(format!("{}.title", path), "Title cannot be empty");
+    }
+    if semver::Version::parse(&mod_item.version).is_err() {
+      report.error(
+        format!("{}.version", path),
+        format!("Invalid version '{}', expected semver", mod_item.version),
+      );
+    }
+    if mod_item.description.is_empty() {
+      report.error(
+        format!("{}.description", path),
+        "Description cannot be empty",
+      );
+    }
+    if mod_item.game_version.is_empty() {
+      report.error(
+        format!("{}.game_version", path),
+        "Game version cannot be empty",
+      );
+    }
+    if mod_item.updated_at.is_empty() {
+      report.error(format!("{}.updated_at", path), "updated_at cannot be empty");
+    } else {
+      check_date(
+        &mut report,
+        format!("{}.updated_at", path),
+        &mod_item.updated_at,
+      );
+    }
+
+    if mod_item.downloads.is_empty() {
+      report.warning(format!("{}.downloads", path), "Mod has no downloads");
+    }
+    check_downloads(
+      &mut report,
+      &format!("{}.downloads", path),
+      &mod_item.downloads,
+      base,
+    );
+
+    for (image_index, image) in mod_item.images.iter().enumerate() {
+      check_url(
+        &mut report,
+        format!("{}.images[{}]", path, image_index),
+        image,
+        base,
+      );
+    }
+
+    check_dependencies(
+      &mut report,
+      &format!("{}.dependencies", path),
+      &mod_item.dependencies,
+    );
+    check_conflicts(
+      &mut report,
+      &format!("{}.conflicts", path),
+      &mod_item.conflicts,
+    );
+
+    for (version_index, release) in mod_item.versions.iter().enumerate() {
+      let version_path = format!("{}.versions[{}]", path, version_index);
+
+      if semver::Version::parse(&release.version).is_err() {
+        report.error(
+          format!("{}.version", version_path),
+          format!("Invalid version '{}', expected semver", release.version),
+        );
+      }
+      if release.downloads.is_empty() {
+        report.warning(
+          format!("{}.downloads", version_path),
+          "Release has no downloads",
+        );
+      }
+      check_downloads(
+        &mut report,
+        &format!("{}.downloads", version_path),
+        &release.downloads,
+        base,
+      );
+      if let Some(updated_at) = &release.updated_at {
+        check_date(
+          &mut report,
+          format!("{}.updated_at", version_path),
+          updated_at,
+        );
+      }
+    }
+  }
+
+  report
+}
+
+/// Warn about dates that are neither RFC 3339 timestamps nor `YYYY-MM-DD`
+fn check_date(report: &mut ValidationReport, path: String, value: &str) {
+  let valid = chrono::DateTime::parse_from_rfc3339(value).is_ok()
+    || chrono::NaiveDate::parse_from_str(value, "%Y-%m-%d").is_ok();
+
+  if !valid {
+    report.warning(
+      path,
+      format!(
+        "Malformed date '{}', expected YYYY-MM-DD or an RFC 3339 timestamp",
+        value
+      ),
+    );
+  }
+}
+
+fn check_downloads(
+  report: &mut ValidationReport,
+  path: &str,
+  downloads: &[Download],
+  base: Option<&reqwest::Url>,
+) {
+  for (index, download) in downloads.iter().enumerate() {
+    let url_path = format!("{}[{}].url", path, index);
+
+    if download.url.is_empty() {
+      report.error(url_path, "Download URL cannot be empty");
+    } else {
+      check_url(report, url_path, &download.url, base);
+    }
+  }
+}
+
+/// Check that a relative URL can be resolved and, for local repositories, that it exists
+fn check_url(report: &mut ValidationReport, path: String, url: &str, base: Option<&reqwest::Url>) {
+  if url.is_empty() || reqwest::Url::parse(url).is_ok() {
+    return;
+  }
+
+  let Some(resolved) = base.and_then(|base| base.join(url).ok()) else {
+    report.warning(
+      path,
+      format!(
+        "Relative path '{}' cannot be resolved without a repository location",
+        url
+      ),
+    );
+    return;
+  };
+
+  if resolved.scheme() == "file" {
+    let exists = resolved.to_file_path().is_ok_and(|file| file.exists());
+    if !exists {
+      report.warning(
+        path,
+        format!("Relative path '{}' points to a missing file", url),
+      );
+    }
+  }
+}
+
+fn check_dependencies(report: &mut ValidationReport, path: &str, dependencies: &[Dependency]) {
+  for (index, dependency) in dependencies.iter().enumerate() {
+    if dependency.id.is_empty() {
+      report.error(
+        format!("{}[{}].id", path, index),
+        "Dependency ID cannot be empty",
+      );
+    }
+    check_range(
+      report,
+      format!("{}[{}].version", path, index),
+      dependency.version.as_deref(),
+    );
+  }
+}
+
+fn check_conflicts(report: &mut ValidationReport, path: &str, conflicts: &[ModConflict]) {
+  for (index, conflict) in conflicts.iter().enumerate() {
+    if conflict.id.is_empty() {
+      report.error(
+        format!("{}[{}].id", path, index),
+        "Conflict ID cannot be empty",
+      );
+    }
+    check_range(
+      report,
+      format!("{}[{}].version", path, index),
+      conflict.version.as_deref(),
+    );
+  }
+}
+
+fn check_range(report: &mut ValidationReport, path: String, range: Option<&str>) {
+  if let Some(range) = range {
+    if semver::VersionReq::parse(range).is_err() {
+      report.error(path, format!("Invalid version range '{}'", range));
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn repository(mods_json: &str) -> ModRepository {
+    serde_json::from_str(&format!(
+      r#"{{"repo_id": "test", "name": "Test", "version": 1, "mods": {}}}"#,
+      mods_json
+    ))
+    .unwrap()
+  }
+
+  #[test]
+  fn test_collects_every_problem_with_its_path() {
+    let repo = repository(
+      r#"[
+        {
+          "id": "a", "title": "", "version": "one", "description": "", "requirements": [],
+          "images": [], "downloads": [{"label": "Download", "url": ""}], "homepage": null,
+          "authors": [], "game_version": "1.0", "updated_at": "yesterday",
+          "dependencies": [{"id": "b", "version": "not a range"}]
+        },
+        {
+          "id": "a", "title": "A", "version": "1.0.0", "description": "Again",
+          "requirements": [], "images": [], "downloads": [], "homepage": null,
+          "authors": [], "game_version": "1.0", "updated_at": "2024-01-01"
+        }
+      ]"#,
+    );
+
+    let report = validate_repository(&repo, None);
+    let paths = |issues: &[ValidationIssue]| -> Vec<String> {
+      issues.iter().map(|issue| issue.path.clone()).collect()
+    };
+
+    assert_eq!(
+      paths(&report.errors),
+      vec![
+        "mods[0].title",
+        "mods[0].version",
+        "mods[0].description",
+        "mods[0].downloads[0].url",
+        "mods[0].dependencies[0].version",
+      ]
+    );
+    assert_eq!(
+      paths(&report.warnings),
+      vec!["mods[0].updated_at", "mods[1].id", "mods[1].downloads"]
+    );
+    assert!(report.into_result().is_err());
+  }
+
+  #[test]
+  fn test_checks_relative_paths_against_the_base() {
+    let local = tempfile::tempdir().unwrap();
+    std::fs::write(local.path().join("present.zip"), b"").unwrap();
+    let base = reqwest::Url::from_directory_path(local.path().canonicalize().unwrap()).unwrap();
+    let repo = repository(
+      r#"[{
+        "id": "a", "title": "A", "version": "1.0.0", "description": "Test", "requirements": [],
+        "images": ["missing.png"], "homepage": null, "authors": [], "game_version": "1.0",
+        "updated_at": "2024-01-01T00:00:00Z",
+        "downloads": [{"label": "Here", "url": "present.zip"}, {"label": "Gone", "url": "gone.zip"}]
+      }]"#,
+    );
+
+    let report = validate_repository(&repo, Some(&base));
+    assert!(report.is_valid());
+    let warnings: Vec<&str> = report.warnings.iter().map(|w| w.path.as_str()).collect();
+    assert_eq!(
+      warnings,
+      vec!["mods[0].downloads[1].url", "mods[0].images[0]"]
+    );
+
+    let report = validate_repository(&repo, None);
+    assert_eq!(report.warnings.len(), 3);
+  }
+}
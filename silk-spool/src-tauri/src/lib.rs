@@ -1,30 +1,51 @@
-mod config;
-mod detect;
-mod installed_mods;
-mod installer;
 mod repository;
-mod steam;
-mod test_repo;
-mod types;
-
-#[cfg(test)]
-mod tests;
-
-use config::{add_repo, load_config, remove_repo, save_config, update_game_path, AppConfig};
-use detect::{get_game_status, validate_game_path, GameStatus};
-use installed_mods::{
-  add_installed_mod, clear_all_installed_mods, get_all_installed_mods, get_installed_mod,
-  get_installed_mods_count, is_mod_installed, load_installed_mods, remove_installed_mod,
-  save_installed_mods, update_mod_version,
-};
-use installer::{install_mod, list_installed_mods, uninstall_mod};
+
 use repository::{
   clear_all_cache_command, clear_repository_cache_command, fetch_repository_command,
-  get_cached_repositories_command, load_cached_repository_command, RepositoryManager,
+  get_cached_repositories_command, get_repository_command, load_cached_repository_command,
+  validate_repository_command,
 };
-use test_repo::test_sample_repository;
-
-use crate::types::{InstallResult, InstalledMod, InstalledModsData};
+use silk_spool_core::{
+  catalog::{load_catalog, Catalog},
+  config::{
+    add_repo, load_config, remove_repo, reorder_repos, save_config, set_offline_mode,
+    update_game_path, update_repo, AppConfig, RepoEntry,
+  },
+  conflicts::{ConflictPolicy, FileConflict},
+  detect::{get_game_status, validate_game_path, GameStatus},
+  doctor::{export_diagnostics, run_doctor, DoctorReport},
+  error::SilkSpoolError,
+  installed_mods::{
+    add_installed_mod, clear_all_installed_mods, get_all_installed_mods, get_file_conflicts,
+    get_installed_mod, get_installed_mods_count, get_installed_mods_for_game, is_mod_installed,
+    load_installed_mods, remove_installed_mod, save_installed_mods, set_mod_pinned,
+    update_mod_version,
+  },
+  installer::{
+    check_install_conflicts, clear_download_cache, disable_mod, enable_mod,
+    install_tracked_version, list_installed_mods, rollback_mod, uninstall_tracked_mod,
+  },
+  logging::{get_log_dir, init_logging, job_span, recent_log_lines},
+  modpack::{
+    export_modpack, import_modpack, read_modpack, write_modpack, Modpack, ModpackImportResult,
+  },
+  profiles::{
+    delete_profile, get_profile, load_profiles, plan_profile_switch, save_profile,
+    snapshot_profile, switch_profile, Profile, ProfileSwitchPlan, ProfileSwitchResult,
+    ProfilesData,
+  },
+  repository::{get_repository_cache_dir, RepositoryManager},
+  resolver::{
+    describe_incompatibilities, execute_install_plan, mod_version, Incompatibility, InstallPlan,
+    InstallPlanResult, Resolver,
+  },
+  schema::repository_json_schema,
+  test_repo::test_sample_repository,
+  types::{InstallResult, InstalledMod, InstalledModEntry, InstalledModsData},
+  updates::{check_updates, update_all, ModUpdate, UpdateAllResult},
+};
+use tauri::State;
+use tracing::Instrument;
 
 // Learn more about Tauri commands at https://tauri.app/develop/calling-rust/
 #[tauri::command]
@@ -34,93 +55,453 @@ fn greet(name: &str) -> String {
 
 /// Detect Steam installation and find Hollow Knight: Silksong
 #[tauri::command]
-async fn detect_game() -> Result<GameStatus, String> {
+async fn detect_game() -> Result<GameStatus, SilkSpoolError> {
   get_game_status()
 }
 
 /// Validate a manually selected game path
 #[tauri::command]
-async fn validate_game_path_command(path: String) -> Result<GameStatus, String> {
+async fn validate_game_path_command(path: String) -> Result<GameStatus, SilkSpoolError> {
   let game_path = std::path::Path::new(&path);
   validate_game_path(game_path)
 }
 
 /// Get current configuration
 #[tauri::command]
-async fn get_config() -> Result<AppConfig, String> {
+async fn get_config() -> Result<AppConfig, SilkSpoolError> {
   load_config()
 }
 
 /// Save configuration
 #[tauri::command]
-async fn save_config_command(config: AppConfig) -> Result<(), String> {
+async fn save_config_command(config: AppConfig) -> Result<(), SilkSpoolError> {
   save_config(&config)
 }
 
-/// Add a repository URL
+/// Add a repository with the lowest priority
 #[tauri::command]
-async fn add_repository(url: String) -> Result<(), String> {
-  add_repo(url)
+async fn add_repository_command(url: String, name: Option<String>) -> Result<(), SilkSpoolError> {
+  add_repo(RepoEntry {
+    name,
+    ..RepoEntry::new(url)
+  })
 }
 
-/// Remove a repository URL
+/// Remove a repository
 #[tauri::command]
-async fn remove_repository(url: String) -> Result<(), String> {
+async fn remove_repository_command(url: String) -> Result<(), SilkSpoolError> {
   remove_repo(&url)
 }
 
+/// Change the settings of a repository, such as its name, auth or whether it is enabled
+#[tauri::command]
+async fn update_repository_command(entry: RepoEntry) -> Result<(), SilkSpoolError> {
+  update_repo(entry)
+}
+
+/// Set repository priorities from URLs ordered highest priority first
+#[tauri::command]
+async fn reorder_repositories_command(urls: Vec<String>) -> Result<(), SilkSpoolError> {
+  reorder_repos(&urls)
+}
+
 /// Update the game path
 #[tauri::command]
-async fn update_game_path_command(path: Option<String>) -> Result<(), String> {
-  let game_path = path.map(|p| std::path::PathBuf::from(p));
+async fn update_game_path_command(path: Option<String>) -> Result<(), SilkSpoolError> {
+  let game_path = path.map(std::path::PathBuf::from);
   update_game_path(game_path)
 }
 
+/// Turn offline mode on or off
 #[tauri::command]
-async fn test_repository_command() -> Result<String, String> {
-  match test_sample_repository() {
-    Ok(_) => Ok("Repository test completed successfully!".to_string()),
-    Err(e) => Err(format!("Repository test failed: {}", e)),
-  }
+async fn set_offline_mode_command(enabled: bool) -> Result<(), SilkSpoolError> {
+  set_offline_mode(enabled)
+}
+
+/// Get the JSON Schema describing the repository format
+#[tauri::command]
+async fn get_repository_schema_command() -> Result<String, SilkSpoolError> {
+  repository_json_schema()
+}
+
+#[tauri::command]
+async fn test_repository_command() -> Result<String, SilkSpoolError> {
+  test_sample_repository()?;
+  Ok("Repository test completed successfully!".to_string())
 }
 
-/// Install a mod from download URL
+/// Install a mod from download URL, replacing and archiving any installed version
+///
+/// Mods the repositories declare incompatible with an installed mod are refused unless
+/// `ignore_incompatibilities` is set. A mod that was disabled stays disabled.
 #[tauri::command]
 async fn install_mod_command(
   download_url: String,
   game_path: String,
   mod_name: String,
-) -> Result<InstallResult, String> {
+  mod_id: String,
+  conflict_policy: Option<ConflictPolicy>,
+  ignore_incompatibilities: Option<bool>,
+  repo_manager: State<'_, RepositoryManager>,
+) -> Result<InstallResult, SilkSpoolError> {
   let game_path = std::path::Path::new(&game_path);
-  install_mod(&download_url, game_path, &mod_name).await
+  let catalog = load_catalog(&repo_manager).await?;
+  let download_url = catalog.resolve_url(&mod_id, &download_url);
+  let version = catalog
+    .find(&mod_id)
+    .and_then(|m| mod_version(&m.definition))
+    .map(|v| v.to_string())
+    .ok_or_else(|| SilkSpoolError::mod_not_found(&mod_id))?;
+
+  if !ignore_incompatibilities.unwrap_or(false) {
+    let repositories = catalog.as_repositories();
+    let installed = get_installed_mods_for_game(game_path)?;
+    let incompatibilities =
+      Resolver::new(&repositories, &installed).incompatibilities_with(&mod_id, &version);
+
+    if !incompatibilities.is_empty() {
+      return Ok(InstallResult {
+        success: false,
+        message: describe_incompatibilities(&incompatibilities).to_string(),
+        installed_files: vec![],
+        mod_folder_name: Some(mod_name),
+        conflicts: vec![],
+      });
+    }
+  }
+
+  let enabled = get_installed_mod(&mod_id, game_path)?.is_none_or(|m| m.enabled);
+  install_tracked_version(
+    &mod_id,
+    &mod_name,
+    &version,
+    &catalog.download_source(&mod_id, &download_url),
+    game_path,
+    enabled,
+    conflict_policy.unwrap_or_default(),
+  )
+  .instrument(job_span("install"))
+  .await?;
+
+  let installed_files = get_installed_mod(&mod_id, game_path)?
+    .map(|m| m.installed_files)
+    .unwrap_or_default();
+  Ok(InstallResult {
+    success: true,
+    message: format!("Installed {} {}", mod_name, version),
+    installed_files,
+    mod_folder_name: Some(mod_name),
+    conflicts: vec![],
+  })
 }
 
-/// Uninstall a mod
+/// Check which files a mod would overwrite before installing it
 #[tauri::command]
-async fn uninstall_mod_command(
+async fn check_install_conflicts_command(
+  download_url: String,
   game_path: String,
   mod_name: String,
-) -> Result<InstallResult, String> {
+  mod_id: Option<String>,
+  repo_manager: State<'_, RepositoryManager>,
+) -> Result<Vec<FileConflict>, SilkSpoolError> {
   let game_path = std::path::Path::new(&game_path);
-  uninstall_mod(game_path, &mod_name)
+  let mod_id = mod_id.unwrap_or_else(|| mod_name.clone());
+  let catalog = load_catalog(&repo_manager).await?;
+  let source = catalog.download_source(&mod_id, &catalog.resolve_url(&mod_id, &download_url));
+  check_install_conflicts(&source, game_path, &mod_name, &mod_id).await
+}
+
+/// Get files claimed by more than one installed mod
+#[tauri::command]
+async fn get_file_conflicts_command() -> Result<Vec<FileConflict>, SilkSpoolError> {
+  get_file_conflicts()
+}
+
+/// Uninstall a tracked mod from a game, whether it is enabled or disabled
+///
+/// Mods that other installed mods depend on are kept unless `force` is set.
+#[tauri::command]
+async fn uninstall_mod_command(
+  mod_id: String,
+  game_path: String,
+  force: Option<bool>,
+  repo_manager: State<'_, RepositoryManager>,
+) -> Result<InstallResult, SilkSpoolError> {
+  let game_path = std::path::Path::new(&game_path);
+
+  if !force.unwrap_or(false) {
+    let repositories = load_catalog(&repo_manager).await?.as_repositories();
+    let installed = get_installed_mods_for_game(game_path)?;
+    let dependents = Resolver::new(&repositories, &installed).dependents_of(&mod_id);
+
+    if !dependents.is_empty() {
+      return Ok(InstallResult {
+        success: false,
+        message: format!("Required by: {}", dependents.join(", ")),
+        installed_files: vec![],
+        mod_folder_name: get_installed_mod(&mod_id, game_path)?.map(|m| m.mod_title),
+        conflicts: vec![],
+      });
+    }
+  }
+
+  job_span("uninstall").in_scope(|| uninstall_tracked_mod(&mod_id, game_path))
+}
+
+/// Build the plan to install a mod and its dependencies into a game
+#[tauri::command]
+async fn resolve_install_plan_command(
+  mod_id: String,
+  game_path: String,
+  repo_manager: State<'_, RepositoryManager>,
+) -> Result<InstallPlan, SilkSpoolError> {
+  let repositories = load_catalog(&repo_manager).await?.as_repositories();
+  let installed = get_installed_mods_for_game(std::path::Path::new(&game_path))?;
+  Resolver::new(&repositories, &installed).plan_install(&mod_id)
+}
+
+/// Install a mod after installing its dependencies
+#[tauri::command]
+async fn install_with_dependencies_command(
+  mod_id: String,
+  game_path: String,
+  ignore_incompatibilities: Option<bool>,
+  repo_manager: State<'_, RepositoryManager>,
+) -> Result<InstallPlanResult, SilkSpoolError> {
+  let game_path = std::path::Path::new(&game_path);
+  let catalog = load_catalog(&repo_manager).await?;
+  let repositories = catalog.as_repositories();
+  let installed = get_installed_mods_for_game(game_path)?;
+  let plan = Resolver::new(&repositories, &installed).plan_install(&mod_id)?;
+
+  if !plan.incompatibilities.is_empty() && !ignore_incompatibilities.unwrap_or(false) {
+    return Err(describe_incompatibilities(&plan.incompatibilities));
+  }
+
+  Ok(
+    execute_install_plan(&plan, game_path, &catalog)
+      .instrument(job_span("install_with_dependencies"))
+      .await,
+  )
+}
+
+/// Find declared incompatibilities among the mods installed in a game
+#[tauri::command]
+async fn check_mod_incompatibilities_command(
+  game_path: String,
+  repo_manager: State<'_, RepositoryManager>,
+) -> Result<Vec<Incompatibility>, SilkSpoolError> {
+  let repositories = load_catalog(&repo_manager).await?.as_repositories();
+  let installed = get_installed_mods_for_game(std::path::Path::new(&game_path))?;
+  Ok(Resolver::new(&repositories, &installed).installed_incompatibilities())
+}
+
+/// Get every cached mod merged into one catalog, ranked by repository priority
+#[tauri::command]
+async fn get_catalog_command(
+  repo_manager: State<'_, RepositoryManager>,
+) -> Result<Catalog, SilkSpoolError> {
+  load_catalog(&repo_manager).await
+}
+
+/// List the mods of a game that have a newer version in the cached repositories
+#[tauri::command]
+async fn check_updates_command(
+  game_path: String,
+  repo_manager: State<'_, RepositoryManager>,
+) -> Result<Vec<ModUpdate>, SilkSpoolError> {
+  let repositories = load_catalog(&repo_manager).await?.as_repositories();
+  let installed = get_installed_mods_for_game(std::path::Path::new(&game_path))?;
+  Ok(check_updates(&repositories, &installed))
+}
+
+/// Upgrade every outdated mod that is not pinned, all or nothing
+#[tauri::command]
+async fn update_all_command(
+  game_path: String,
+  repo_manager: State<'_, RepositoryManager>,
+) -> Result<UpdateAllResult, SilkSpoolError> {
+  let catalog = load_catalog(&repo_manager).await?;
+  let game_path = std::path::Path::new(&game_path);
+  update_all(&catalog, game_path)
+    .instrument(job_span("update_all"))
+    .await
+}
+
+/// List the mods installed in a game that depend on a mod
+#[tauri::command]
+async fn get_dependents_command(
+  mod_id: String,
+  game_path: String,
+  repo_manager: State<'_, RepositoryManager>,
+) -> Result<Vec<String>, SilkSpoolError> {
+  let repositories = load_catalog(&repo_manager).await?.as_repositories();
+  let installed = get_installed_mods_for_game(std::path::Path::new(&game_path))?;
+  Ok(Resolver::new(&repositories, &installed).dependents_of(&mod_id))
 }
 
 /// List installed mods
 #[tauri::command]
-async fn list_installed_mods_command(game_path: String) -> Result<Vec<String>, String> {
+async fn list_installed_mods_command(
+  game_path: String,
+) -> Result<Vec<InstalledModEntry>, SilkSpoolError> {
   let game_path = std::path::Path::new(&game_path);
   list_installed_mods(game_path)
 }
 
+/// Disable a mod without uninstalling it
+#[tauri::command]
+async fn disable_mod_command(
+  mod_id: String,
+  game_path: String,
+) -> Result<InstallResult, SilkSpoolError> {
+  job_span("disable").in_scope(|| disable_mod(&mod_id, std::path::Path::new(&game_path)))
+}
+
+/// Re-enable a disabled mod
+#[tauri::command]
+async fn enable_mod_command(
+  mod_id: String,
+  game_path: String,
+) -> Result<InstallResult, SilkSpoolError> {
+  job_span("enable").in_scope(|| enable_mod(&mod_id, std::path::Path::new(&game_path)))
+}
+
+/// Restore the version a mod had before its last update
+#[tauri::command]
+async fn rollback_mod_command(
+  mod_id: String,
+  game_path: String,
+  repo_manager: State<'_, RepositoryManager>,
+) -> Result<InstallResult, SilkSpoolError> {
+  let local_root = load_catalog(&repo_manager).await?.local_root(&mod_id);
+  rollback_mod(
+    &mod_id,
+    std::path::Path::new(&game_path),
+    local_root.as_deref(),
+  )
+  .instrument(job_span("rollback"))
+  .await
+}
+
+/// Hold a mod at its installed version so bulk updates skip it
+#[tauri::command]
+async fn pin_mod_command(mod_id: String, game_path: String) -> Result<(), SilkSpoolError> {
+  set_mod_pinned(&mod_id, std::path::Path::new(&game_path), true)
+}
+
+/// Let bulk updates upgrade a pinned mod again
+#[tauri::command]
+async fn unpin_mod_command(mod_id: String, game_path: String) -> Result<(), SilkSpoolError> {
+  set_mod_pinned(&mod_id, std::path::Path::new(&game_path), false)
+}
+
+/// Remove every cached mod download
+#[tauri::command]
+async fn clear_download_cache_command() -> Result<(), SilkSpoolError> {
+  clear_download_cache()
+}
+
+/// Get all mod profiles and the active one
+#[tauri::command]
+async fn get_profiles_command() -> Result<ProfilesData, SilkSpoolError> {
+  load_profiles()
+}
+
+/// Create or replace a mod profile
+#[tauri::command]
+async fn save_profile_command(profile: Profile) -> Result<(), SilkSpoolError> {
+  save_profile(profile)
+}
+
+/// Save the mods currently installed in a game directory as a profile
+#[tauri::command]
+async fn snapshot_profile_command(
+  name: String,
+  game_path: String,
+) -> Result<Profile, SilkSpoolError> {
+  snapshot_profile(&name, std::path::Path::new(&game_path))
+}
+
+/// Delete a mod profile
+#[tauri::command]
+async fn delete_profile_command(name: String) -> Result<(), SilkSpoolError> {
+  delete_profile(&name)
+}
+
+/// Preview the changes switching a game directory to a profile would make
+#[tauri::command]
+async fn plan_profile_switch_command(
+  name: String,
+  game_path: String,
+) -> Result<ProfileSwitchPlan, SilkSpoolError> {
+  let profile = get_profile(&name)?;
+  let installed = get_installed_mods_for_game(std::path::Path::new(&game_path))?;
+  Ok(plan_profile_switch(&profile, &installed))
+}
+
+/// Switch the installed mods to match a profile
+#[tauri::command]
+async fn switch_profile_command(
+  name: String,
+  game_path: String,
+  repo_manager: State<'_, RepositoryManager>,
+) -> Result<ProfileSwitchResult, SilkSpoolError> {
+  let game_path = std::path::Path::new(&game_path);
+  let catalog = load_catalog(&repo_manager).await?;
+  switch_profile(&name, game_path, &catalog)
+    .instrument(job_span("switch_profile"))
+    .await
+}
+
+/// Export the installed mods, or a profile, as a modpack file
+#[tauri::command]
+async fn export_modpack_command(
+  name: String,
+  output_path: String,
+  game_path: String,
+  profile: Option<String>,
+  include_configs: bool,
+  repo_manager: State<'_, RepositoryManager>,
+) -> Result<Modpack, SilkSpoolError> {
+  let game_path = std::path::Path::new(&game_path);
+  let modpack = export_modpack(
+    &name,
+    profile.as_deref(),
+    game_path,
+    include_configs,
+    &repo_manager,
+  )
+  .await?;
+
+  write_modpack(&modpack, std::path::Path::new(&output_path))?;
+  Ok(modpack)
+}
+
+/// Import a modpack file and install everything in it
+#[tauri::command]
+async fn import_modpack_command(
+  path: String,
+  game_path: String,
+  add_missing_repos: bool,
+  repo_manager: State<'_, RepositoryManager>,
+) -> Result<ModpackImportResult, SilkSpoolError> {
+  let modpack = read_modpack(std::path::Path::new(&path))?;
+  let game_path = std::path::Path::new(&game_path);
+  import_modpack(&modpack, game_path, add_missing_repos, &repo_manager)
+    .instrument(job_span("import_modpack"))
+    .await
+}
+
 /// Get installed mods data
 #[tauri::command]
-async fn get_installed_mods() -> Result<InstalledModsData, String> {
+async fn get_installed_mods() -> Result<InstalledModsData, SilkSpoolError> {
   load_installed_mods()
 }
 
 /// Save installed mods data
 #[tauri::command]
-async fn save_installed_mods_command(data: InstalledModsData) -> Result<(), String> {
+async fn save_installed_mods_command(data: InstalledModsData) -> Result<(), SilkSpoolError> {
   save_installed_mods(&data)
 }
 
@@ -133,7 +514,7 @@ async fn add_installed_mod_command(
   installed_files: Vec<String>,
   game_path: String,
   download_url: Option<String>,
-) -> Result<(), String> {
+) -> Result<(), SilkSpoolError> {
   add_installed_mod(
     mod_id,
     mod_title,
@@ -146,25 +527,34 @@ async fn add_installed_mod_command(
 
 /// Remove installed mod
 #[tauri::command]
-async fn remove_installed_mod_command(mod_id: String) -> Result<(), String> {
-  remove_installed_mod(&mod_id)
+async fn remove_installed_mod_command(
+  mod_id: String,
+  game_path: String,
+) -> Result<(), SilkSpoolError> {
+  remove_installed_mod(&mod_id, std::path::Path::new(&game_path))
 }
 
 /// Get installed mod info
 #[tauri::command]
-async fn get_installed_mod_command(mod_id: String) -> Result<Option<InstalledMod>, String> {
-  get_installed_mod(&mod_id)
+async fn get_installed_mod_command(
+  mod_id: String,
+  game_path: String,
+) -> Result<Option<InstalledMod>, SilkSpoolError> {
+  get_installed_mod(&mod_id, std::path::Path::new(&game_path))
 }
 
 /// Check if mod is installed
 #[tauri::command]
-async fn is_mod_installed_command(mod_id: String) -> Result<bool, String> {
-  is_mod_installed(&mod_id)
+async fn is_mod_installed_command(
+  mod_id: String,
+  game_path: String,
+) -> Result<bool, SilkSpoolError> {
+  is_mod_installed(&mod_id, std::path::Path::new(&game_path))
 }
 
 /// Get all installed mods
 #[tauri::command]
-async fn get_all_installed_mods_command() -> Result<Vec<InstalledMod>, String> {
+async fn get_all_installed_mods_command() -> Result<Vec<InstalledMod>, SilkSpoolError> {
   get_all_installed_mods()
 }
 
@@ -172,35 +562,71 @@ async fn get_all_installed_mods_command() -> Result<Vec<InstalledMod>, String> {
 #[tauri::command]
 async fn update_mod_version_command(
   mod_id: String,
+  game_path: String,
   new_version: String,
   new_installed_files: Vec<String>,
-) -> Result<(), String> {
-  update_mod_version(&mod_id, new_version, new_installed_files)
+) -> Result<(), SilkSpoolError> {
+  update_mod_version(
+    &mod_id,
+    std::path::Path::new(&game_path),
+    new_version,
+    new_installed_files,
+  )
 }
 
 /// Clear all installed mods
 #[tauri::command]
-async fn clear_all_installed_mods_command() -> Result<(), String> {
+async fn clear_all_installed_mods_command() -> Result<(), SilkSpoolError> {
   clear_all_installed_mods()
 }
 
 /// Get installed mods count
 #[tauri::command]
-async fn get_installed_mods_count_command() -> Result<usize, String> {
+async fn get_installed_mods_count_command() -> Result<usize, SilkSpoolError> {
   get_installed_mods_count()
 }
 
+/// Check the game, the tracked mods and the repositories for common problems
+#[tauri::command]
+async fn run_doctor_command(
+  game_path: Option<String>,
+  repo_manager: State<'_, RepositoryManager>,
+) -> Result<DoctorReport, SilkSpoolError> {
+  let game_path = game_path.as_deref().map(std::path::Path::new);
+  Ok(run_doctor(game_path, &repo_manager).await)
+}
+
+/// Run the doctor and save its report, the configuration and the logs as a zip for a bug report
+#[tauri::command]
+async fn export_diagnostics_command(
+  output_path: String,
+  game_path: Option<String>,
+  repo_manager: State<'_, RepositoryManager>,
+) -> Result<DoctorReport, SilkSpoolError> {
+  let game_path = game_path.as_deref().map(std::path::Path::new);
+  let report = run_doctor(game_path, &repo_manager).await;
+  export_diagnostics(&report, std::path::Path::new(&output_path))?;
+  Ok(report)
+}
+
+/// Get the most recent lines of the app log, for attaching to bug reports
+#[tauri::command]
+async fn get_recent_logs_command(lines: Option<usize>) -> Result<Vec<String>, SilkSpoolError> {
+  recent_log_lines(&get_log_dir()?, lines.unwrap_or(200))
+}
+
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
+  // The app still runs when the log file cannot be opened, it just logs nothing
+  let _log_guard = get_log_dir()
+    .and_then(|log_dir| init_logging(&log_dir, cfg!(debug_assertions)))
+    .map_err(|e| eprintln!("{}", e))
+    .ok();
+
   tauri::Builder::default()
     .plugin(tauri_plugin_opener::init())
     .manage(RepositoryManager::new(
-      dirs::cache_dir()
-        .unwrap_or_else(|| std::env::temp_dir())
-        .join("silk-spool")
-        .join("repositories")
-        .to_string_lossy()
-        .to_string(),
+      get_repository_cache_dir().to_string_lossy().to_string(),
     ))
     .invoke_handler(tauri::generate_handler![
       greet,
@@ -208,18 +634,47 @@ pub fn run() {
       validate_game_path_command,
       get_config,
       save_config_command,
-      add_repository,
-      remove_repository,
+      add_repository_command,
+      remove_repository_command,
+      update_repository_command,
+      reorder_repositories_command,
       update_game_path_command,
+      set_offline_mode_command,
       fetch_repository_command,
+      get_repository_command,
       get_cached_repositories_command,
       load_cached_repository_command,
+      validate_repository_command,
       clear_repository_cache_command,
       clear_all_cache_command,
       test_repository_command,
+      get_repository_schema_command,
       install_mod_command,
+      check_install_conflicts_command,
+      get_file_conflicts_command,
       uninstall_mod_command,
+      resolve_install_plan_command,
+      install_with_dependencies_command,
+      get_dependents_command,
+      check_mod_incompatibilities_command,
+      get_catalog_command,
+      check_updates_command,
+      update_all_command,
       list_installed_mods_command,
+      disable_mod_command,
+      enable_mod_command,
+      rollback_mod_command,
+      pin_mod_command,
+      unpin_mod_command,
+      clear_download_cache_command,
+      get_profiles_command,
+      save_profile_command,
+      snapshot_profile_command,
+      delete_profile_command,
+      plan_profile_switch_command,
+      switch_profile_command,
+      export_modpack_command,
+      import_modpack_command,
       get_installed_mods,
       save_installed_mods_command,
       add_installed_mod_command,
@@ -229,7 +684,10 @@ pub fn run() {
       get_all_installed_mods_command,
       update_mod_version_command,
       clear_all_installed_mods_command,
-      get_installed_mods_count_command
+      get_installed_mods_count_command,
+      get_recent_logs_command,
+      run_doctor_command,
+      export_diagnostics_command
     ])
     .run(tauri::generate_context!())
     .expect("error while running tauri application");
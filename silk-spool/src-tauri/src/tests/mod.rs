@@ -1,5 +0,0 @@
-// Test modules
-mod basic_tests;
-
-// Re-export test modules
-pub use basic_tests::*;
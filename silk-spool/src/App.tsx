@@ -12,6 +12,7 @@ import {
   StatusBar,
 } from './components';
 import { FilterOptions } from './components/AdvancedFilters';
+import { errorMessage } from './services/errors';
 import { ImageCacheService } from './services/imageCacheService';
 import { installedModsService } from './services/installedModsService';
 import { RepositoryService } from './services/repositoryService';
@@ -32,7 +33,7 @@ function App() {
   const [isScanning, setIsScanning] = useState(true);
   const [searchQuery, setSearchQuery] = useState('');
   const [selectedMod, setSelectedMod] = useState<Mod | null>(null);
-  const [activeRepoId, setActiveRepoId] = useState<string | null>(null);
+  const [activeRepoUrl, setActiveRepoUrl] = useState<string | null>(null);
   const [mods, setMods] = useState<Mod[]>([]);
   const [filteredMods, setFilteredMods] = useState<Mod[]>([]);
   const [repositories, setRepositories] = useState<RepositoryInfo[]>([]);
@@ -58,7 +59,7 @@ function App() {
       }
     } catch (error) {
       console.error('Path validation failed:', error);
-      alert(`Failed to validate path: ${error}`);
+      alert(`Failed to validate path: ${errorMessage(error)}`);
     }
   };
 
@@ -81,9 +82,9 @@ function App() {
     setSelectedMod(mod);
   };
 
-  const handleRepoSelect = async (repoId: string | null) => {
-    setActiveRepoId(repoId);
-    await loadMods(repoId);
+  const handleRepoSelect = async (repoUrl: string | null) => {
+    setActiveRepoUrl(repoUrl);
+    await loadMods(repoUrl);
   };
 
   const handleAddRepo = () => {
@@ -93,7 +94,7 @@ function App() {
   const handleRepositoryAdded = async () => {
     // Refresh repositories and mods
     await loadRepositories();
-    await loadMods(activeRepoId);
+    await loadMods(activeRepoUrl);
   };
 
   const loadRepositories = async () => {
@@ -105,13 +106,13 @@ function App() {
     }
   };
 
-  const loadMods = async (repoId: string | null) => {
+  const loadMods = async (repoUrl: string | null) => {
     setIsLoadingMods(true);
     try {
       let mods: Mod[] = [];
 
-      if (repoId) {
-        mods = await RepositoryService.getModsFromRepository(repoId);
+      if (repoUrl) {
+        mods = await RepositoryService.getModsFromRepository(repoUrl);
       } else {
         mods = await RepositoryService.getAllMods();
       }
@@ -151,7 +152,7 @@ function App() {
           bepinex: {
             present: false,
             initialized: false,
-            message: `Detection error: ${error}`,
+            message: `Detection error: ${errorMessage(error)}`,
           },
         });
       }
@@ -163,7 +164,7 @@ function App() {
       await detectGame();
       await loadRepositories();
 
-      // Load mods from all repositories by default (activeRepoId = null)
+      // Load mods from all repositories by default (activeRepoUrl = null)
       await loadMods(null);
     };
 
@@ -197,7 +198,7 @@ function App() {
             <div className='flex space-x-2'>
               <RepoSelector
                 repositories={repositories}
-                activeRepoId={activeRepoId}
+                activeRepoUrl={activeRepoUrl}
                 onRepoSelect={handleRepoSelect}
                 onAddRepo={handleAddRepo}
                 className='flex-1'
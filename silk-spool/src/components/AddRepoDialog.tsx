@@ -1,4 +1,5 @@
 import React, { useState } from 'react';
+import { errorMessage } from '../services/errors';
 import { RepositoryService } from '../services/repositoryService';
 
 interface AddRepoDialogProps {
@@ -51,10 +52,10 @@ export const AddRepoDialog: React.FC<AddRepoDialogProps> = ({
           setError('Failed to add repository to configuration');
         }
       } else {
-        setError(response.error || 'Failed to fetch repository');
+        setError(response.error?.message || 'Failed to fetch repository');
       }
     } catch (err) {
-      setError(`Failed to add repository: ${err}`);
+      setError(`Failed to add repository: ${errorMessage(err)}`);
     } finally {
       setIsLoading(false);
     }
@@ -98,7 +99,7 @@ export const AddRepoDialog: React.FC<AddRepoDialogProps> = ({
             </label>
             <input
               id='repo-url'
-              type='url'
+              type='text'
               value={url}
               onChange={e => setUrl(e.target.value)}
               placeholder='https://example.com/mods.json'
@@ -107,7 +108,8 @@ export const AddRepoDialog: React.FC<AddRepoDialogProps> = ({
               required
             />
             <p className='text-xs text-gray-400 mt-1'>
-              Enter the URL to a JSON file containing mod repository data
+              Enter the URL or local path of a JSON file (or folder with a mods.json) containing
+              mod repository data
             </p>
           </div>
 
@@ -66,7 +66,7 @@ export const ModDetail: React.FC<ModDetailProps> = ({ mod, gamePath, className =
     setLoadingDownload(downloadLabel);
     try {
       // Install the mod using the installer service (notifications are handled by the service)
-      await InstallerService.installMod(downloadUrl, gamePath, mod, downloadLabel);
+      await InstallerService.installMod(downloadUrl, gamePath, mod);
     } catch (error) {
       console.error('Failed to install mod:', error);
     } finally {
@@ -91,13 +91,13 @@ export const ModDetail: React.FC<ModDetailProps> = ({ mod, gamePath, className =
   };
 
   const handleUninstall = async () => {
-    if (!gamePath || !mod) {
+    if (!mod || !gamePath) {
       return;
     }
 
     setLoadingUninstall(true);
     try {
-      await InstallerService.uninstallMod(gamePath, mod);
+      await InstallerService.uninstallMod(mod, gamePath);
     } catch (error) {
       console.error('Failed to uninstall mod:', error);
     } finally {
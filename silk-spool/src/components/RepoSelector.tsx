@@ -3,23 +3,23 @@ import { RepositoryInfo } from '../types';
 
 interface RepoSelectorProps {
   repositories: RepositoryInfo[];
-  activeRepoId: string | null;
-  onRepoSelect: (repoId: string | null) => void;
+  activeRepoUrl: string | null;
+  onRepoSelect: (repoUrl: string | null) => void;
   onAddRepo: () => void;
   className?: string;
 }
 
 export const RepoSelector: React.FC<RepoSelectorProps> = ({
   repositories,
-  activeRepoId,
+  activeRepoUrl,
   onRepoSelect,
   onAddRepo,
   className = '',
 }) => {
   const [isOpen, setIsOpen] = useState(false);
 
-  const handleRepoSelect = (repoId: string | null) => {
-    onRepoSelect(repoId);
+  const handleRepoSelect = (repoUrl: string | null) => {
+    onRepoSelect(repoUrl);
     setIsOpen(false);
   };
 
@@ -49,13 +49,13 @@ export const RepoSelector: React.FC<RepoSelectorProps> = ({
           />
         </svg>
         <span className='text-sm'>
-          {activeRepoId
-            ? repositories.find(r => r.id === activeRepoId)?.name || 'All Repositories'
+          {activeRepoUrl
+            ? repositories.find(r => r.url === activeRepoUrl)?.name || 'All Repositories'
             : 'All Repositories'}
         </span>
         <span className='bg-primary-600 text-white text-xs px-2 py-1 rounded-full'>
-          {activeRepoId
-            ? repositories.find(r => r.id === activeRepoId)?.mod_count || 0
+          {activeRepoUrl
+            ? repositories.find(r => r.url === activeRepoUrl)?.mod_count || 0
             : getTotalModCount()}
         </span>
         <svg
@@ -75,7 +75,7 @@ export const RepoSelector: React.FC<RepoSelectorProps> = ({
             <button
               onClick={() => handleRepoSelect(null)}
               className={`w-full text-left px-3 py-2 rounded-md transition-colors duration-200 ${
-                activeRepoId === null
+                activeRepoUrl === null
                   ? 'bg-primary-600 text-white'
                   : 'text-gray-300 hover:bg-gray-700'
               }`}
@@ -91,10 +91,10 @@ export const RepoSelector: React.FC<RepoSelectorProps> = ({
             {/* Repository List */}
             {repositories.map(repo => (
               <button
-                key={repo.id}
-                onClick={() => handleRepoSelect(repo.id)}
+                key={repo.url}
+                onClick={() => handleRepoSelect(repo.url)}
                 className={`w-full text-left px-3 py-2 rounded-md transition-colors duration-200 ${
-                  activeRepoId === repo.id
+                  activeRepoUrl === repo.url
                     ? 'bg-primary-600 text-white'
                     : 'text-gray-300 hover:bg-gray-700'
                 }`}
@@ -6,6 +6,7 @@ import { Mod } from '../../types';
 const mockMod: Mod = {
   id: 'test-mod',
   title: 'Test Mod',
+  version: '1.0.0',
   description: 'A test mod for testing purposes',
   homepage: 'https://example.com',
   game_version: '1.0.0',
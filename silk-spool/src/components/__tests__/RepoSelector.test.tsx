@@ -30,7 +30,7 @@ describe('RepoSelector', () => {
     render(
       <RepoSelector
         repositories={mockRepositories}
-        activeRepoId='built-in'
+        activeRepoUrl='built-in'
         onRepoSelect={mockOnRepoSelect}
         onAddRepo={mockOnAddRepo}
       />
@@ -47,7 +47,7 @@ describe('RepoSelector', () => {
     render(
       <RepoSelector
         repositories={mockRepositories}
-        activeRepoId={null}
+        activeRepoUrl={null}
         onRepoSelect={mockOnRepoSelect}
         onAddRepo={mockOnAddRepo}
       />
@@ -64,7 +64,7 @@ describe('RepoSelector', () => {
     render(
       <RepoSelector
         repositories={mockRepositories}
-        activeRepoId='built-in'
+        activeRepoUrl='built-in'
         onRepoSelect={mockOnRepoSelect}
         onAddRepo={mockOnAddRepo}
       />
@@ -84,7 +84,7 @@ describe('RepoSelector', () => {
     render(
       <RepoSelector
         repositories={mockRepositories}
-        activeRepoId='built-in'
+        activeRepoUrl='built-in'
         onRepoSelect={mockOnRepoSelect}
         onAddRepo={mockOnAddRepo}
       />
@@ -106,7 +106,7 @@ describe('RepoSelector', () => {
     render(
       <RepoSelector
         repositories={mockRepositories}
-        activeRepoId='built-in'
+        activeRepoUrl='built-in'
         onRepoSelect={mockOnRepoSelect}
         onAddRepo={mockOnAddRepo}
       />
@@ -128,7 +128,7 @@ describe('RepoSelector', () => {
     render(
       <RepoSelector
         repositories={mockRepositories}
-        activeRepoId='built-in'
+        activeRepoUrl='built-in'
         onRepoSelect={mockOnRepoSelect}
         onAddRepo={mockOnAddRepo}
       />
@@ -147,7 +147,7 @@ describe('RepoSelector', () => {
     render(
       <RepoSelector
         repositories={mockRepositories}
-        activeRepoId='built-in'
+        activeRepoUrl='built-in'
         onRepoSelect={mockOnRepoSelect}
         onAddRepo={mockOnAddRepo}
       />
@@ -0,0 +1,26 @@
+import { invoke } from '@tauri-apps/api/core';
+import { DoctorReport } from '../types';
+
+/**
+ * Check the game, the tracked mods and the repositories for common problems
+ */
+export async function runDoctor(gamePath?: string): Promise<DoctorReport> {
+  return invoke<DoctorReport>('run_doctor_command', { gamePath });
+}
+
+/**
+ * Run the doctor and save a zip with its report, the configuration and the logs
+ */
+export async function exportDiagnostics(
+  outputPath: string,
+  gamePath?: string
+): Promise<DoctorReport> {
+  return invoke<DoctorReport>('export_diagnostics_command', { outputPath, gamePath });
+}
+
+/**
+ * True when no check failed; warnings are allowed
+ */
+export function isHealthy(report: DoctorReport): boolean {
+  return report.checks.every(check => check.status !== 'error');
+}
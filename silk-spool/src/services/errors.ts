@@ -0,0 +1,30 @@
+import { SilkSpoolError } from '../types';
+
+/**
+ * Check whether a rejected command returned a backend error
+ */
+export function isSilkSpoolError(error: unknown): error is SilkSpoolError {
+  return (
+    typeof error === 'object' &&
+    error !== null &&
+    typeof (error as SilkSpoolError).code === 'string' &&
+    typeof (error as SilkSpoolError).message === 'string'
+  );
+}
+
+/**
+ * Turn anything thrown by a command into a backend error
+ */
+export function toSilkSpoolError(error: unknown): SilkSpoolError {
+  if (isSilkSpoolError(error)) {
+    return error;
+  }
+  return { code: 'other', message: error instanceof Error ? error.message : String(error) };
+}
+
+/**
+ * Get a message for the user from anything thrown by a command
+ */
+export function errorMessage(error: unknown): string {
+  return toSilkSpoolError(error).message;
+}
@@ -80,6 +80,8 @@ class InstalledModsService {
     gamePath: string,
     downloadUrl?: string
   ): Promise<void> {
+    // Replacing an install keeps its version history and pin, so rollback still works
+    const existing = this.getInstalledMod(modId, gamePath);
     const installedMod: InstalledMod = {
       modId,
       modTitle,
@@ -88,10 +90,13 @@ class InstalledModsService {
       installedFiles,
       gamePath,
       downloadUrl,
+      enabled: true,
+      pinnedVersion: existing?.pinnedVersion ? version : undefined,
+      history: existing?.history ?? [],
     };
 
-    // Remove existing mod with same ID if present
-    this.installedMods = this.installedMods.filter(mod => mod.modId !== modId);
+    // Remove the existing install of this mod in the same game, if present
+    this.installedMods = this.installedMods.filter(mod => mod !== existing);
 
     // Add new mod
     this.installedMods.push(installedMod);
@@ -132,10 +137,12 @@ class InstalledModsService {
   }
 
   /**
-   * Get installed mod info
+   * Get installed mod info, optionally for one game directory
    */
-  public getInstalledMod(modId: string): InstalledMod | undefined {
-    return this.installedMods.find(mod => mod.modId === modId);
+  public getInstalledMod(modId: string, gamePath?: string): InstalledMod | undefined {
+    return this.installedMods.find(
+      mod => mod.modId === modId && (gamePath === undefined || mod.gamePath === gamePath)
+    );
   }
 
   /**
@@ -1,5 +1,6 @@
 import { invoke } from '@tauri-apps/api/core';
-import { InstallResult, Mod } from '../types';
+import { InstallResult, InstalledModEntry, Mod } from '../types';
+import { errorMessage, isSilkSpoolError } from './errors';
 import { notificationService } from './notificationService';
 import { installedModsService } from './installedModsService';
 
@@ -10,8 +11,7 @@ export class InstallerService {
   static async installMod(
     downloadUrl: string,
     gamePath: string,
-    mod: Mod,
-    downloadLabel?: string
+    mod: Mod
   ): Promise<InstallResult> {
     // Show initial notification
     const notificationId = notificationService.progress(
@@ -25,35 +25,19 @@ export class InstallerService {
       notificationService.updateProgress(notificationId, 10);
       notificationService.updateProgress(notificationId, 25);
 
+      // The backend records the install and archives the version it replaces
       const result = await invoke<InstallResult>('install_mod_command', {
         downloadUrl,
         gamePath,
         modName: mod.title,
+        modId: mod.id,
       });
 
       if (result.success) {
         // Update progress to completion
         notificationService.updateProgress(notificationId, 100);
 
-        // Extract version from download label or use game_version as fallback
-        let modVersion = mod.game_version; // Default fallback
-        if (downloadLabel) {
-          // Try to extract version from download label (e.g., "Download v2.0.2" -> "2.0.2")
-          const versionMatch = downloadLabel.match(/v?(\d+\.\d+\.\d+)/i);
-          if (versionMatch) {
-            modVersion = versionMatch[1];
-          }
-        }
-
-        // Track the installed mod
-        await installedModsService.addInstalledMod(
-          mod.id,
-          mod.title,
-          modVersion,
-          result.installed_files,
-          gamePath,
-          downloadUrl
-        );
+        await installedModsService.loadInstalledMods();
 
         // Dismiss progress notification and show success
         notificationService.dismiss(notificationId);
@@ -77,36 +61,43 @@ export class InstallerService {
 
       // Dismiss progress notification and show error
       notificationService.dismiss(notificationId);
-      notificationService.error('Installation Failed', `Failed to install ${mod.title}: ${error}`);
+      const message =
+        isSilkSpoolError(error) && error.code === 'bepinex_missing'
+          ? 'BepInEx is not installed in the game folder. Install BepInEx, then try again.'
+          : errorMessage(error);
+      notificationService.error(
+        'Installation Failed',
+        `Failed to install ${mod.title}: ${message}`
+      );
 
       return {
         success: false,
-        message: `Installation failed: ${error}`,
+        message: `Installation failed: ${message}`,
         installed_files: [],
       };
     }
   }
 
   /**
-   * Uninstall a mod
+   * Uninstall a mod from a game, keeping it when other installed mods depend on it unless
+   * `force` is set
    */
-  static async uninstallMod(gamePath: string, mod: Mod): Promise<InstallResult> {
+  static async uninstallMod(mod: Mod, gamePath: string, force = false): Promise<InstallResult> {
     try {
-      // Get the installed mod info to find the folder name
-      const installedMod = installedModsService.getInstalledMod(mod.id);
-      if (!installedMod) {
+      if (!installedModsService.getInstalledMod(mod.id, gamePath)) {
         throw new Error('Mod not found in installed mods list');
       }
 
-      // Use the mod title as folder name (same as installation)
+      // The backend removes exactly the files it recorded, wherever a disabled mod keeps them
       const result = await invoke<InstallResult>('uninstall_mod_command', {
+        modId: mod.id,
         gamePath,
-        modName: mod.title,
+        force,
       });
 
       if (result.success) {
-        // Remove from installed mods tracking
-        await installedModsService.removeInstalledMod(mod.id);
+        // The backend already dropped the record, so pick up its list
+        await installedModsService.loadInstalledMods();
 
         notificationService.success(
           'Uninstallation Complete',
@@ -125,11 +116,11 @@ export class InstallerService {
       console.error('Failed to uninstall mod:', error);
       notificationService.error(
         'Uninstallation Failed',
-        `Failed to uninstall ${mod.title}: ${error}`
+        `Failed to uninstall ${mod.title}: ${errorMessage(error)}`
       );
       return {
         success: false,
-        message: `Uninstallation failed: ${error}`,
+        message: `Uninstallation failed: ${errorMessage(error)}`,
         installed_files: [],
       };
     }
@@ -138,9 +129,9 @@ export class InstallerService {
   /**
    * List installed mods
    */
-  static async listInstalledMods(gamePath: string): Promise<string[]> {
+  static async listInstalledMods(gamePath: string): Promise<InstalledModEntry[]> {
     try {
-      return await invoke<string[]>('list_installed_mods_command', {
+      return await invoke<InstalledModEntry[]>('list_installed_mods_command', {
         gamePath,
       });
     } catch (error) {
@@ -155,7 +146,7 @@ export class InstallerService {
   static async isModInstalled(gamePath: string, modName: string): Promise<boolean> {
     try {
       const installedMods = await this.listInstalledMods(gamePath);
-      return installedMods.includes(modName);
+      return installedMods.some(entry => entry.name === modName);
     } catch (error) {
       console.error('Failed to check if mod is installed:', error);
       return false;
@@ -0,0 +1,15 @@
+import { invoke } from '@tauri-apps/api/core';
+
+/**
+ * Get the most recent lines of the app log, oldest first
+ */
+export async function getRecentLogs(lines = 200): Promise<string[]> {
+  return invoke<string[]>('get_recent_logs_command', { lines });
+}
+
+/**
+ * Get the recent log as one block of text, ready to paste into a bug report
+ */
+export async function getRecentLogText(lines = 200): Promise<string> {
+  return (await getRecentLogs(lines)).join('\n');
+}
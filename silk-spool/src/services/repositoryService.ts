@@ -1,5 +1,48 @@
 import { invoke } from '@tauri-apps/api/core';
-import { Repository, RepositoryInfo, RepositoryResponse, Mod } from '../types';
+import {
+  Repository,
+  RepositoryInfo,
+  RepositoryResponse,
+  RepositoryResult,
+  RepoEntry,
+  Mod,
+  ValidationReport,
+} from '../types';
+import { toSilkSpoolError } from './errors';
+
+const OFFICIAL_REPOSITORY_URL =
+  'https://raw.githubusercontent.com/FrancescoGrazioso/SilkSpool-sources/refs/heads/main/silkspool-sources.json';
+const BUILT_IN_REPOSITORY_URL = '/mods.json';
+
+/**
+ * Resolve download and image URLs given relative to the repository file
+ */
+function resolveRelativeUrls(repository: Repository, baseUrl: string): Repository {
+  const resolve = (url: string) => {
+    if (!url) return url;
+    try {
+      return new URL(url, baseUrl).toString();
+    } catch {
+      return url;
+    }
+  };
+
+  return {
+    ...repository,
+    mods: repository.mods.map(mod => ({
+      ...mod,
+      images: mod.images.map(resolve),
+      downloads: mod.downloads.map(download => ({ ...download, url: resolve(download.url) })),
+      versions: mod.versions?.map(version => ({
+        ...version,
+        downloads: version.downloads.map(download => ({
+          ...download,
+          url: resolve(download.url),
+        })),
+      })),
+    })),
+  };
+}
 
 export class RepositoryService {
   /**
@@ -11,11 +54,37 @@ export class RepositoryService {
     } catch (error) {
       return {
         success: false,
-        error: `Failed to fetch repository: ${error}`,
+        error: toSilkSpoolError(error),
       };
     }
   }
 
+  /**
+   * Get a repository, falling back to a stale cached copy when it cannot be fetched
+   */
+  static async getRepository(url: string): Promise<RepositoryResult | null> {
+    try {
+      return await invoke<RepositoryResult>('get_repository_command', { url });
+    } catch (error) {
+      console.error(`Failed to get repository ${url}:`, error);
+      return null;
+    }
+  }
+
+  /**
+   * Check a repository and list every error and warning in it
+   */
+  static async validateRepository(url: string): Promise<ValidationReport> {
+    return await invoke<ValidationReport>('validate_repository_command', { url });
+  }
+
+  /**
+   * Get the JSON Schema describing the repository format
+   */
+  static async getRepositorySchema(): Promise<string> {
+    return await invoke<string>('get_repository_schema_command');
+  }
+
   /**
    * Get all cached repositories
    */
@@ -31,11 +100,11 @@ export class RepositoryService {
   /**
    * Load a specific cached repository
    */
-  static async loadCachedRepository(repoId: string): Promise<Repository | null> {
+  static async loadCachedRepository(url: string): Promise<Repository | null> {
     try {
-      return await invoke<Repository>('load_cached_repository_command', { repoId });
+      return await invoke<Repository>('load_cached_repository_command', { url });
     } catch (error) {
-      console.error(`Failed to load repository ${repoId}:`, error);
+      console.error(`Failed to load repository ${url}:`, error);
       return null;
     }
   }
@@ -43,12 +112,12 @@ export class RepositoryService {
   /**
    * Clear cache for a specific repository
    */
-  static async clearRepositoryCache(repoId: string): Promise<boolean> {
+  static async clearRepositoryCache(url: string): Promise<boolean> {
     try {
-      await invoke('clear_repository_cache_command', { repoId });
+      await invoke('clear_repository_cache_command', { url });
       return true;
     } catch (error) {
-      console.error(`Failed to clear cache for repository ${repoId}:`, error);
+      console.error(`Failed to clear cache for repository ${url}:`, error);
       return false;
     }
   }
@@ -67,11 +136,11 @@ export class RepositoryService {
   }
 
   /**
-   * Add a repository URL to the configuration
+   * Add a repository to the configuration
    */
-  static async addRepository(url: string): Promise<boolean> {
+  static async addRepository(url: string, name?: string): Promise<boolean> {
     try {
-      await invoke('add_repository', { url });
+      await invoke('add_repository_command', { url, name });
       return true;
     } catch (error) {
       console.error('Failed to add repository:', error);
@@ -84,7 +153,7 @@ export class RepositoryService {
    */
   static async removeRepository(url: string): Promise<boolean> {
     try {
-      await invoke('remove_repository', { url });
+      await invoke('remove_repository_command', { url });
       return true;
     } catch (error) {
       console.error('Failed to remove repository:', error);
@@ -92,17 +161,43 @@ export class RepositoryService {
     }
   }
 
+  /**
+   * Change the settings of a configured repository
+   */
+  static async updateRepository(entry: RepoEntry): Promise<boolean> {
+    try {
+      await invoke('update_repository_command', { entry });
+      return true;
+    } catch (error) {
+      console.error('Failed to update repository:', error);
+      return false;
+    }
+  }
+
+  /**
+   * Set repository priorities from URLs ordered highest priority first
+   */
+  static async reorderRepositories(urls: string[]): Promise<boolean> {
+    try {
+      await invoke('reorder_repositories_command', { urls });
+      return true;
+    } catch (error) {
+      console.error('Failed to reorder repositories:', error);
+      return false;
+    }
+  }
+
   /**
    * Load the built-in mods.json repository
    */
   static async loadBuiltInRepository(): Promise<Repository | null> {
     try {
-      const response = await fetch('/mods.json');
+      const response = await fetch(BUILT_IN_REPOSITORY_URL);
       if (!response.ok) {
         console.log('No built-in mods.json found');
         return null;
       }
-      return await response.json();
+      return resolveRelativeUrls(await response.json(), response.url);
     } catch (error) {
       console.log('Failed to load built-in repository:', error);
       return null;
@@ -114,14 +209,12 @@ export class RepositoryService {
    */
   static async loadOfficialRepository(): Promise<Repository | null> {
     try {
-      const response = await fetch(
-        'https://raw.githubusercontent.com/FrancescoGrazioso/SilkSpool-sources/refs/heads/main/silkspool-sources.json'
-      );
+      const response = await fetch(OFFICIAL_REPOSITORY_URL);
       if (!response.ok) {
         console.log('Failed to fetch official repository');
         return null;
       }
-      return await response.json();
+      return resolveRelativeUrls(await response.json(), response.url);
     } catch (error) {
       console.log('Failed to load official repository:', error);
       return null;
@@ -144,7 +237,7 @@ export class RepositoryService {
         allRepos.unshift({
           id: 'official',
           name: officialRepo.name,
-          url: 'https://raw.githubusercontent.com/FrancescoGrazioso/SilkSpool-sources/refs/heads/main/silkspool-sources.json',
+          url: OFFICIAL_REPOSITORY_URL,
           version: officialRepo.version,
           mod_count: officialRepo.mods.length,
         });
@@ -155,7 +248,7 @@ export class RepositoryService {
         allRepos.unshift({
           id: 'built-in',
           name: builtInRepo.name,
-          url: '/mods.json',
+          url: BUILT_IN_REPOSITORY_URL,
           version: builtInRepo.version,
           mod_count: builtInRepo.mods.length,
         });
@@ -190,7 +283,7 @@ export class RepositoryService {
       // Load cached repositories
       const repositories = await this.getCachedRepositories();
       for (const repoInfo of repositories) {
-        const repository = await this.loadCachedRepository(repoInfo.id);
+        const repository = await this.loadCachedRepository(repoInfo.url);
         if (repository) {
           allMods.push(...repository.mods);
         }
@@ -204,22 +297,22 @@ export class RepositoryService {
   }
 
   /**
-   * Get mods from a specific repository
+   * Get mods from the repository at a URL
    */
-  static async getModsFromRepository(repoId: string): Promise<Mod[]> {
+  static async getModsFromRepository(repoUrl: string): Promise<Mod[]> {
     try {
-      if (repoId === 'official') {
+      if (repoUrl === OFFICIAL_REPOSITORY_URL) {
         const repository = await this.loadOfficialRepository();
         return repository ? repository.mods : [];
-      } else if (repoId === 'built-in') {
+      } else if (repoUrl === BUILT_IN_REPOSITORY_URL) {
         const repository = await this.loadBuiltInRepository();
         return repository && repository.mods.length > 0 ? repository.mods : [];
       } else {
-        const repository = await this.loadCachedRepository(repoId);
+        const repository = await this.loadCachedRepository(repoUrl);
         return repository ? repository.mods : [];
       }
     } catch (error) {
-      console.error(`Failed to get mods from repository ${repoId}:`, error);
+      console.error(`Failed to get mods from repository ${repoUrl}:`, error);
       return [];
     }
   }
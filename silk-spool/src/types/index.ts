@@ -3,6 +3,7 @@
 export interface Mod {
   id: string;
   title: string;
+  version: string;
   description: string;
   requirements: string[];
   images: string[];
@@ -11,6 +12,29 @@ export interface Mod {
   authors: string[];
   game_version: string;
   updated_at: string;
+  dependencies?: Dependency[];
+  conflicts?: ModConflict[];
+  provides?: string[];
+  versions?: ModVersion[];
+}
+
+export interface ModVersion {
+  version: string;
+  downloads: Download[];
+  game_version?: string;
+  updated_at?: string;
+}
+
+export interface Dependency {
+  id: string;
+  version?: string;
+  optional?: boolean;
+  repo?: string;
+}
+
+export interface ModConflict {
+  id: string;
+  version?: string;
 }
 
 export interface Download {
@@ -19,6 +43,8 @@ export interface Download {
 }
 
 export interface Repository {
+  /** Version of the repository format; absent in files that predate versioning */
+  schema_version?: number;
   repo_id: string;
   name: string;
   version: number;
@@ -37,7 +63,78 @@ export interface RepositoryInfo {
 export interface RepositoryResponse {
   success: boolean;
   data?: Repository;
-  error?: string;
+  error?: SilkSpoolError;
+}
+
+export interface CatalogRepository {
+  repo_id: string;
+  name: string;
+  url: string;
+  version: number;
+}
+
+export interface CatalogMod extends Mod {
+  repo_id: string;
+  repo_url: string;
+  shadowed: string[];
+}
+
+export interface Catalog {
+  repositories: CatalogRepository[];
+  mods: CatalogMod[];
+}
+
+export interface RepositoryResult {
+  repository: Repository;
+  stale: boolean;
+  fetched_at?: string;
+  error?: SilkSpoolError;
+}
+
+export type SilkSpoolErrorCode =
+  | 'game_not_found'
+  | 'invalid_game_path'
+  | 'bepinex_missing'
+  | 'offline'
+  | 'network'
+  | 'http_status'
+  | 'checksum_mismatch'
+  | 'io'
+  | 'parse'
+  | 'archive'
+  | 'invalid_repository'
+  | 'signature_mismatch'
+  | 'repository_not_configured'
+  | 'mod_not_found'
+  | 'download_unavailable'
+  | 'dependency'
+  | 'incompatible'
+  | 'file_conflict'
+  | 'profile_not_found'
+  | 'invalid_input'
+  | 'other';
+
+/** An error returned by a backend command */
+export interface SilkSpoolError {
+  code: SilkSpoolErrorCode;
+  message: string;
+  path?: string;
+  url?: string;
+  mod_id?: string;
+}
+
+export type ValidationSeverity = 'error' | 'warning';
+
+export interface ValidationIssue {
+  severity: ValidationSeverity;
+  /** Location of the offending value, e.g. `mods[3].downloads[0].url` */
+  path: string;
+  message: string;
+}
+
+export interface ValidationReport {
+  errors: ValidationIssue[];
+  warnings: ValidationIssue[];
 }
 
 export interface GameStatus {
@@ -52,9 +149,23 @@ export interface BepInExStatus {
   message: string;
 }
 
+export type RepoAuth =
+  | { type: 'bearer'; token: string }
+  | { type: 'basic'; username: string; password: string };
+
+export interface RepoEntry {
+  url: string;
+  name?: string;
+  enabled: boolean;
+  priority: number;
+  pinned_key?: string;
+  auth?: RepoAuth;
+  refresh_interval_minutes?: number;
+}
+
 export interface AppConfig {
   gamePath: string | null;
-  repos: string[];
+  repos: RepoEntry[];
   ui: {
     theme: 'dark' | 'light';
     windowSize: {
@@ -113,9 +224,64 @@ export interface InstalledMod {
   installedFiles: string[];
   gamePath: string;
   downloadUrl?: string;
+  enabled?: boolean;
+  pinnedVersion?: string;
+  history?: ModVersionRecord[];
+}
+
+export interface ModVersionRecord {
+  version: string;
+  installedFiles: string[];
+  downloadUrl?: string;
+  replacedAt: string;
+  archivePath?: string;
+}
+
+export interface ModUpdate {
+  mod_id: string;
+  title: string;
+  installed_version: string;
+  available_version: string;
+  repo_id: string;
+  download_url: string;
+  pinned: boolean;
+}
+
+export type UpdateStatus = 'updated' | 'installed' | 'pinned' | 'failed' | 'cancelled';
+
+export interface ModUpdateResult {
+  mod_id: string;
+  from_version?: string;
+  to_version: string;
+  status: UpdateStatus;
+  error?: SilkSpoolError;
+}
+
+export interface UpdateAllResult {
+  success: boolean;
+  results: ModUpdateResult[];
+  rollback_errors: string[];
+}
+
+export interface InstalledModEntry {
+  name: string;
+  enabled: boolean;
 }
 
 export interface InstalledModsData {
   mods: InstalledMod[];
   lastUpdated: string;
 }
+
+export type CheckStatus = 'ok' | 'warning' | 'error';
+
+export interface DoctorCheck {
+  name: string;
+  status: CheckStatus;
+  message: string;
+}
+
+export interface DoctorReport {
+  game_path?: string;
+  checks: DoctorCheck[];
+}